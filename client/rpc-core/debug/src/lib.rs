@@ -32,7 +32,8 @@ pub struct TraceParams {
 	pub disable_storage: Option<bool>,
 	pub disable_memory: Option<bool>,
 	pub disable_stack: Option<bool>,
-	/// Javascript tracer (we just check if it's Blockscout tracer string)
+	/// Name of a native tracer (`prestateTracer`, `4byteTracer` or `opcodeHistogramTracer`), or
+	/// a javascript tracer (we just check if it's Blockscout tracer string)
 	pub tracer: Option<String>,
 	pub timeout: Option<String>,
 }
@@ -14,11 +14,17 @@
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
-use ethereum_types::H160;
+use ethereum_types::{H160, H256};
 use futures::{compat::Compat, future::BoxFuture};
 use jsonrpc_derive::rpc;
 pub use edgeware_rpc_primitives_debug::block::TransactionTrace;
-use serde::{de::Error, Deserialize, Deserializer};
+use edgeware_rpc_primitives_debug::{
+	block::{TransactionTraceAction, TransactionTraceOutput},
+	serialization::bytes_0x_serialize,
+	single::AccountDiff,
+};
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 
 pub use rpc_impl_Trace::gen_server::Trace as TraceServer;
 
@@ -29,6 +35,13 @@ pub trait Trace {
 		&self,
 		filter: FilterRequest,
 	) -> Compat<BoxFuture<'static, jsonrpc_core::Result<Vec<TransactionTrace>>>>;
+
+	#[rpc(name = "trace_replayTransaction")]
+	fn replay_transaction(
+		&self,
+		transaction_hash: H256,
+		trace_types: Vec<ReplayTraceType>,
+	) -> Compat<BoxFuture<'static, jsonrpc_core::Result<ReplayResults>>>;
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Deserialize)]
@@ -53,6 +66,54 @@ pub struct FilterRequest {
 	pub count: Option<u32>,
 }
 
+/// Kind of trace `trace_replayTransaction` can return.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReplayTraceType {
+	/// Calls and subcalls, as `trace_filter` returns them.
+	Trace,
+	/// Changes to the accounts touched.
+	StateDiff,
+	/// Not supported.
+	VmTrace,
+}
+
+/// Result of `trace_replayTransaction`. Traces that were not requested are `null`.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayResults {
+	/// Value returned by the transaction.
+	#[serde(serialize_with = "bytes_0x_serialize")]
+	pub output: Vec<u8>,
+	pub state_diff: Option<BTreeMap<H160, AccountDiff>>,
+	pub trace: Option<Vec<ReplayTrace>>,
+	/// Always `null`, VM traces are not supported.
+	pub vm_trace: Option<()>,
+}
+
+/// A `trace_filter` trace, without the block and transaction it belongs to.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayTrace {
+	#[serde(flatten)]
+	pub action: TransactionTraceAction,
+	#[serde(flatten)]
+	pub output: TransactionTraceOutput,
+	pub subtraces: u32,
+	pub trace_address: Vec<u32>,
+}
+
+impl From<TransactionTrace> for ReplayTrace {
+	fn from(trace: TransactionTrace) -> Self {
+		ReplayTrace {
+			action: trace.action,
+			output: trace.output,
+			subtraces: trace.subtraces,
+			trace_address: trace.trace_address,
+		}
+	}
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Deserialize)]
 #[serde(rename_all = "camelCase", untagged)]
 pub enum RequestBlockId {
@@ -31,7 +31,7 @@ use fc_rpc::{frontier_backend_client, internal_err};
 use fp_rpc::EthereumRuntimeRPCApi;
 use edgeware_rpc_primitives_debug::{single, DebugRuntimeApi};
 use sc_client_api::backend::Backend;
-use sp_api::{BlockId, HeaderT, ProvideRuntimeApi};
+use sp_api::{ApiExt, BlockId, HeaderT, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{
 	Backend as BlockchainBackend, Error as BlockChainError, HeaderBackend, HeaderMetadata,
@@ -41,7 +41,7 @@ use sp_utils::mpsc::TracingUnboundedSender;
 use std::{future::Future, marker::PhantomData, str::FromStr, sync::Arc};
 
 pub type Responder = oneshot::Sender<RpcResult<single::TransactionTrace>>;
-pub type DebugRequester = TracingUnboundedSender<((H256, Option<TraceParams>), Responder)>;
+pub type DebugRequester = TracingUnboundedSender<((H256, single::TraceType), Responder)>;
 
 pub struct Debug {
 	pub requester: DebugRequester,
@@ -61,31 +61,76 @@ impl DebugT for Debug {
 		transaction_hash: H256,
 		params: Option<TraceParams>,
 	) -> Compat<BoxFuture<'static, RpcResult<single::TransactionTrace>>> {
-		let mut requester = self.requester.clone();
-
-		async move {
-			let (tx, rx) = oneshot::channel();
-			// Send a message from the rpc handler to the service level task.
-			requester
-				.send(((transaction_hash, params), tx))
-				.await
-				.map_err(|err| {
-					internal_err(format!(
-						"failed to send request to debug service : {:?}",
-						err
-					))
-				})?;
-
-			// Receive a message from the service level task and send the rpc response.
-			rx.await.map_err(|err| {
-				internal_err(format!("debug service dropped the channel : {:?}", err))
-			})?
-		}
-		.boxed()
-		.compat()
+		let requester = self.requester.clone();
+
+		async move { replay(requester, transaction_hash, trace_type(params)?).await }
+			.boxed()
+			.compat()
 	}
 }
 
+/// Trace type requested by the `debug_traceTransaction` parameters.
+fn trace_type(params: Option<TraceParams>) -> RpcResult<single::TraceType> {
+	Ok(match params {
+		Some(TraceParams {
+			tracer: Some(tracer),
+			..
+		}) => match tracer.as_str() {
+			// Native tracers, by the name geth gives them.
+			"prestateTracer" => single::TraceType::Prestate,
+			"4byteTracer" => single::TraceType::FourByte,
+			// Native tracer with no geth equivalent.
+			"opcodeHistogramTracer" => single::TraceType::OpcodeHistogram,
+			// Otherwise only Blockscout's javascript tracer is recognised.
+			tracer => {
+				let hash: H128 = sp_io::hashing::twox_128(&tracer.as_bytes()).into();
+				let blockscout_hash = H128::from_str("0x94d9f08796f91eb13a2e82a6066882f7").unwrap();
+				if hash == blockscout_hash {
+					single::TraceType::CallList
+				} else {
+					return Err(internal_err(format!(
+						"javascript based tracing is not available (hash :{:?})",
+						hash
+					)));
+				}
+			}
+		},
+		Some(params) => single::TraceType::Raw {
+			disable_storage: params.disable_storage.unwrap_or(false),
+			disable_memory: params.disable_memory.unwrap_or(false),
+			disable_stack: params.disable_stack.unwrap_or(false),
+		},
+		_ => single::TraceType::Raw {
+			disable_storage: false,
+			disable_memory: false,
+			disable_stack: false,
+		},
+	})
+}
+
+/// Replay a transaction with the debug service, tracing it with `trace_type`.
+pub async fn replay(
+	mut requester: DebugRequester,
+	transaction_hash: H256,
+	trace_type: single::TraceType,
+) -> RpcResult<single::TransactionTrace> {
+	let (tx, rx) = oneshot::channel();
+	// Send a message from the rpc handler to the service level task.
+	requester
+		.send(((transaction_hash, trace_type), tx))
+		.await
+		.map_err(|err| {
+			internal_err(format!(
+				"failed to send request to debug service : {:?}",
+				err
+			))
+		})?;
+
+	// Receive a message from the service level task and send the rpc response.
+	rx.await
+		.map_err(|err| internal_err(format!("debug service dropped the channel : {:?}", err)))?
+}
+
 pub struct DebugHandler<B: BlockT, C, BE>(PhantomData<(B, C, BE)>);
 
 impl<B, C, BE> DebugHandler<B, C, BE>
@@ -112,7 +157,7 @@ where
 
 		let fut = async move {
 			loop {
-				if let Some(((transaction_hash, params), response_tx)) = rx.next().await {
+				if let Some(((transaction_hash, trace_type), response_tx)) = rx.next().await {
 					let client = client.clone();
 					let backend = backend.clone();
 					let frontier_backend = frontier_backend.clone();
@@ -136,7 +181,7 @@ where
 										backend.clone(),
 										frontier_backend.clone(),
 										transaction_hash,
-										params,
+										trace_type,
 									)
 								})
 								.await
@@ -167,7 +212,7 @@ where
 		backend: Arc<BE>,
 		frontier_backend: Arc<fc_db::Backend<B>>,
 		transaction_hash: H256,
-		params: Option<TraceParams>,
+		trace_type: single::TraceType,
 	) -> RpcResult<single::TransactionTrace> {
 		let (hash, index) = match frontier_backend_client::load_transactions::<B, C>(
 			client.as_ref(),
@@ -195,6 +240,18 @@ where
 		// Get parent blockid.
 		let parent_block_id = BlockId::Hash(*header.parent_hash());
 
+		// Runtimes older than version 2 of the api cannot decode the newer trace types.
+		if requires_api_v2(&trace_type) {
+			let supported = api
+				.has_api_with::<dyn DebugRuntimeApi<B>, _>(&parent_block_id, |version| version >= 2)
+				.map_err(|e| internal_err(format!("Runtime api access error: {:?}", e)))?;
+			if !supported {
+				return Err(internal_err(
+					"Tracer not supported by the runtime of this block".to_string(),
+				));
+			}
+		}
+
 		// Get the extrinsics.
 		let ext = blockchain.body(reference_id).unwrap().unwrap();
 
@@ -204,35 +261,6 @@ where
 			Err(e) => return Err(internal_err(format!("Runtime block call failed: {:?}", e))),
 		};
 
-		// Set trace type
-		let trace_type = match params {
-			Some(TraceParams {
-				tracer: Some(tracer),
-				..
-			}) => {
-				let hash: H128 = sp_io::hashing::twox_128(&tracer.as_bytes()).into();
-				let blockscout_hash = H128::from_str("0x94d9f08796f91eb13a2e82a6066882f7").unwrap();
-				if hash == blockscout_hash {
-					single::TraceType::CallList
-				} else {
-					return Err(internal_err(format!(
-						"javascript based tracing is not available (hash :{:?})",
-						hash
-					)));
-				}
-			}
-			Some(params) => single::TraceType::Raw {
-				disable_storage: params.disable_storage.unwrap_or(false),
-				disable_memory: params.disable_memory.unwrap_or(false),
-				disable_stack: params.disable_stack.unwrap_or(false),
-			},
-			_ => single::TraceType::Raw {
-				disable_storage: false,
-				disable_memory: false,
-				disable_stack: false,
-			},
-		};
-
 		// Get the actual ethereum transaction.
 		if let Some(block) = reference_block {
 			let transactions = block.transactions;
@@ -247,3 +275,13 @@ where
 		return Err(internal_err("Runtime block call failed".to_string()));
 	}
 }
+
+fn requires_api_v2(trace_type: &single::TraceType) -> bool {
+	match trace_type {
+		single::TraceType::Raw { .. } | single::TraceType::CallList => false,
+		single::TraceType::Prestate
+		| single::TraceType::StateDiff
+		| single::TraceType::FourByte
+		| single::TraceType::OpcodeHistogram => true,
+	}
+}
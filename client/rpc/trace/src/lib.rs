@@ -16,7 +16,7 @@
 
 //! `trace_filter` RPC handler and its associated service task.
 //! The RPC handler rely on `CacheTask` which provides a future that must be run inside a tokio
-//! executor.
+//! executor. `trace_replayTransaction` is handled by the debug service task.
 //!
 //! The implementation is composed of multiple tasks :
 //! - Many calls the the RPC handler `Trace::filter`, communicating with the main task.
@@ -53,15 +53,19 @@ use fc_rpc::internal_err;
 use fp_rpc::EthereumRuntimeRPCApi;
 
 pub use edgeware_rpc_core_trace::{
-	FilterRequest, RequestBlockId, RequestBlockTag, Trace as TraceT, TraceServer, TransactionTrace,
+	FilterRequest, ReplayResults, ReplayTrace, ReplayTraceType, RequestBlockId, RequestBlockTag,
+	Trace as TraceT, TraceServer, TransactionTrace,
 };
-use edgeware_rpc_primitives_debug::{block, DebugRuntimeApi};
+use edgeware_rpc_debug::{replay, DebugRequester};
+use edgeware_rpc_primitives_debug::{block, single, DebugRuntimeApi};
 
-/// RPC handler. Will communicate with a `CacheTask` through a `CacheRequester`.
+/// RPC handler. Will communicate with a `CacheTask` through a `CacheRequester`, and with the
+/// debug service through a `DebugRequester` to replay transactions.
 pub struct Trace<B, C> {
 	_phantom: PhantomData<B>,
 	client: Arc<C>,
 	requester: CacheRequester,
+	debug_requester: DebugRequester,
 	max_count: u32,
 }
 
@@ -71,6 +75,7 @@ impl<B, C> Clone for Trace<B, C> {
 			_phantom: PhantomData::default(),
 			client: Arc::clone(&self.client),
 			requester: self.requester.clone(),
+			debug_requester: self.debug_requester.clone(),
 			max_count: self.max_count,
 		}
 	}
@@ -84,10 +89,16 @@ where
 	C: Send + Sync + 'static,
 {
 	/// Create a new RPC handler.
-	pub fn new(client: Arc<C>, requester: CacheRequester, max_count: u32) -> Self {
+	pub fn new(
+		client: Arc<C>,
+		requester: CacheRequester,
+		debug_requester: DebugRequester,
+		max_count: u32,
+	) -> Self {
 		Self {
 			client,
 			requester,
+			debug_requester,
 			max_count,
 			_phantom: PhantomData::default(),
 		}
@@ -160,6 +171,71 @@ where
 		res
 	}
 
+	/// `trace_replayTransaction` endpoint (wrapped in the trait implementation with futures
+	/// compatibility)
+	async fn replay_transaction(
+		self,
+		transaction_hash: H256,
+		trace_types: Vec<ReplayTraceType>,
+	) -> Result<ReplayResults> {
+		if trace_types.contains(&ReplayTraceType::VmTrace) {
+			return Err(internal_err("'vmTrace' is not supported"));
+		}
+
+		let state_diff = if trace_types.contains(&ReplayTraceType::StateDiff) {
+			let trace_type = single::TraceType::StateDiff;
+			match replay(self.debug_requester.clone(), transaction_hash, trace_type).await? {
+				single::TransactionTrace::StateDiff { output, state_diff } => {
+					Some((output, state_diff))
+				}
+				_ => return Err(internal_err("Runtime returned the wrong trace type")),
+			}
+		} else {
+			None
+		};
+
+		let trace = if trace_types.contains(&ReplayTraceType::Trace) {
+			let trace_type = single::TraceType::CallList;
+			match replay(self.debug_requester.clone(), transaction_hash, trace_type).await? {
+				single::TransactionTrace::CallList(calls) => Some(
+					calls
+						.into_iter()
+						.map(|call| block::TransactionTrace::from_call(call, 0).into())
+						.collect::<Vec<ReplayTrace>>(),
+				),
+				_ => return Err(internal_err("Runtime returned the wrong trace type")),
+			}
+		} else {
+			None
+		};
+
+		// The output comes with the state diff, or is the one of the top-level call.
+		let output = match (&state_diff, &trace) {
+			(Some((output, _)), _) => output.clone(),
+			(None, Some(trace)) => trace
+				.iter()
+				.find(|trace| trace.trace_address.is_empty())
+				.and_then(|trace| match &trace.output {
+					block::TransactionTraceOutput::Result(
+						block::TransactionTraceResult::Call { output, .. },
+					) => Some(output.clone()),
+					block::TransactionTraceOutput::Result(
+						block::TransactionTraceResult::Create { code, .. },
+					) => Some(code.clone()),
+					_ => None,
+				})
+				.unwrap_or_default(),
+			(None, None) => vec![],
+		};
+
+		Ok(ReplayResults {
+			output,
+			state_diff: state_diff.map(|(_, state_diff)| state_diff),
+			trace,
+			vm_trace: None,
+		})
+	}
+
 	async fn fetch_traces(
 		&self,
 		req: FilterRequest,
@@ -246,6 +322,18 @@ where
 		// Wraps the async function into futures compatibility layer.
 		self.clone().filter(filter).boxed().compat()
 	}
+
+	fn replay_transaction(
+		&self,
+		transaction_hash: H256,
+		trace_types: Vec<ReplayTraceType>,
+	) -> Compat<BoxFuture<'static, jsonrpc_core::Result<ReplayResults>>> {
+		// Wraps the async function into futures compatibility layer.
+		self.clone()
+			.replay_transaction(transaction_hash, trace_types)
+			.boxed()
+			.compat()
+	}
 }
 
 /// An opaque batch ID.
@@ -18,8 +18,9 @@
 
 use edgeware_runtime::{
 	constants::currency::*, wasm_binary_unwrap, AuraConfig, AuthorityDiscoveryConfig, BalancesConfig, Block,
-	CouncilConfig, DemocracyConfig, EVMConfig, GrandpaConfig, ImOnlineConfig, IndicesConfig, SessionConfig,
-	SessionKeys, StakerStatus, StakingConfig, SudoConfig, SystemConfig, TreasuryRewardConfig, VestingConfig,
+	CouncilConfig, DemocracyConfig, EVMConfig, EdgewarePrecompiles, GrandpaConfig, ImOnlineConfig, IndicesConfig,
+	Runtime, SessionConfig, SessionKeys, StakerStatus, StakingConfig, SudoConfig, SystemConfig, TreasuryRewardConfig,
+	VestingConfig,
 };
 use pallet_im_online::ed25519::AuthorityId as ImOnlineId;
 use sc_chain_spec::ChainSpecExtension;
@@ -163,7 +164,7 @@ pub fn testnet_genesis(
 	create_evm_alice: bool,
 ) -> GenesisConfig {
 	let alice_evm_account_id = H160::from_str("19e7e376e7c213b7e7e7e46cc70a5dd086daff2a").unwrap();
-	let mut evm_accounts = BTreeMap::new();
+	let mut evm_accounts = EdgewarePrecompiles::<Runtime>::genesis_accounts();
 
 	if create_evm_alice {
 		evm_accounts.insert(alice_evm_account_id, pallet_evm::GenesisAccount {
@@ -252,6 +253,10 @@ pub fn testnet_genesis(
 		treasury_reward: TreasuryRewardConfig {
 			current_payout: 95 * DOLLARS,
 			minting_interval: One::one(),
+			payout_schedule: Default::default(),
+			recipients: vec![],
+			minting_cap: None,
+			minting_end: None,
 		},
 	}
 }
@@ -487,10 +492,16 @@ pub fn mainnet_genesis(
 		},
 		pallet_vesting: VestingConfig { vesting },
 		pallet_ethereum: Default::default(),
-		pallet_evm: Default::default(),
+		pallet_evm: EVMConfig {
+			accounts: EdgewarePrecompiles::<Runtime>::genesis_accounts(),
+		},
 		treasury_reward: TreasuryRewardConfig {
 			current_payout: 95 * DOLLARS,
 			minting_interval: One::one(),
+			payout_schedule: Default::default(),
+			recipients: vec![],
+			minting_cap: None,
+			minting_end: None,
 		},
 	}
 }
@@ -21,6 +21,7 @@ use crate::serialization::*;
 #[cfg(feature = "std")]
 use serde::Serialize;
 
+use crate::{single, CallResult, CreateResult, CreateType};
 use codec::{Decode, Encode};
 use ethereum_types::{H160, H256, U256};
 use sp_std::vec::Vec;
@@ -43,6 +44,84 @@ pub struct TransactionTrace {
 	pub transaction_position: u32,
 }
 
+impl TransactionTrace {
+	/// Convert a trace from the "single" call list format, for the transaction at
+	/// `transaction_position` in its block.
+	///
+	/// The block hash and number and the transaction hash can't be known in the runtime, and
+	/// must be inserted upstream.
+	pub fn from_call(call: single::Call, transaction_position: u32) -> Self {
+		let (action, output) = match call.inner {
+			single::CallInner::Call {
+				input,
+				to,
+				res,
+				call_type,
+			} => (
+				TransactionTraceAction::Call {
+					call_type,
+					from: call.from,
+					gas: call.gas,
+					input,
+					to,
+					value: call.value,
+				},
+				match res {
+					CallResult::Output(output) => {
+						TransactionTraceOutput::Result(TransactionTraceResult::Call {
+							gas_used: call.gas_used,
+							output,
+						})
+					}
+					CallResult::Error(error) => TransactionTraceOutput::Error(error),
+				},
+			),
+			single::CallInner::Create { init, res } => (
+				TransactionTraceAction::Create {
+					creation_method: CreateType::Create,
+					from: call.from,
+					gas: call.gas,
+					init,
+					value: call.value,
+				},
+				match res {
+					CreateResult::Success {
+						created_contract_address_hash,
+						created_contract_code,
+					} => TransactionTraceOutput::Result(TransactionTraceResult::Create {
+						gas_used: call.gas_used,
+						code: created_contract_code,
+						address: created_contract_address_hash,
+					}),
+					CreateResult::Error { error } => TransactionTraceOutput::Error(error),
+				},
+			),
+			single::CallInner::SelfDestruct {
+				balance,
+				refund_address,
+			} => (
+				TransactionTraceAction::Suicide {
+					address: call.from,
+					balance,
+					refund_address,
+				},
+				TransactionTraceOutput::Result(TransactionTraceResult::Suicide),
+			),
+		};
+
+		TransactionTrace {
+			action,
+			block_hash: H256::default(),
+			block_number: 0,
+			output,
+			subtraces: call.subtraces,
+			trace_address: call.trace_address,
+			transaction_hash: H256::default(),
+			transaction_position,
+		}
+	}
+}
+
 #[derive(Clone, Eq, PartialEq, Debug, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Serialize))]
 #[cfg_attr(
@@ -25,6 +25,8 @@ use sp_std::vec::Vec;
 use serde::Serialize;
 
 sp_api::decl_runtime_apis! {
+	// Version 2 adds the `Prestate`, `StateDiff`, `FourByte` and `OpcodeHistogram` trace types.
+	#[api_version(2)]
 	pub trait DebugRuntimeApi {
 		fn trace_transaction(
 			extrinsics: Vec<Block::Extrinsic>,
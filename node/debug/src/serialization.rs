@@ -18,9 +18,10 @@
 
 use ethereum_types::{H256, U256};
 use serde::{
-	ser::{Error, SerializeSeq},
+	ser::{Error, SerializeMap, SerializeSeq},
 	Serializer,
 };
+use std::collections::BTreeMap;
 
 pub fn seq_h256_serialize<S>(data: &Option<Vec<H256>>, serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -81,3 +82,34 @@ where
 {
 	serializer.serialize_str(&format!("0x{:x}", data))
 }
+
+pub fn four_byte_serialize<S>(
+	data: &BTreeMap<([u8; 4], u32), u64>,
+	serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	let mut map = serializer.serialize_map(Some(data.len()))?;
+	for ((selector, size), count) in data {
+		map.serialize_entry(&format!("0x{}-{}", hex::encode(selector), size), count)?;
+	}
+	map.end()
+}
+
+pub fn opcode_histogram_serialize<S>(
+	data: &BTreeMap<Vec<u8>, u64>,
+	serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	let mut map = serializer.serialize_map(Some(data.len()))?;
+	for (opcode, count) in data {
+		let opcode = std::str::from_utf8(opcode)
+			.map_err(|_| S::Error::custom("Opcode serialize error."))?
+			.to_uppercase();
+		map.serialize_entry(&opcode, count)?;
+	}
+	map.end()
+}
@@ -17,7 +17,9 @@
 //! Types for the tracing of a single Ethereum transaction.
 //! Structure from "raw" debug_trace and a "call list" matching
 //! Blockscout formatter. This "call list" is also used to build
-//! the whole block tracing output.
+//! the whole block tracing output. The "prestate" matches geth's
+//! `prestateTracer`, the "4byte" geth's `4byteTracer`, and the "state diff"
+//! OpenEthereum's `stateDiff`.
 
 #[cfg(feature = "std")]
 use crate::serialization::*;
@@ -38,6 +40,14 @@ pub enum TraceType {
 	},
 	/// List of calls and subcalls (output Blockscout expects).
 	CallList,
+	/// State of the accounts touched, before the transaction (geth's `prestateTracer`).
+	Prestate,
+	/// Changes to the accounts touched (OpenEthereum's `stateDiff`).
+	StateDiff,
+	/// Number of calls by function selector and calldata size (geth's `4byteTracer`).
+	FourByte,
+	/// Number of times each opcode was executed.
+	OpcodeHistogram,
 }
 
 /// Single transaction trace.
@@ -56,8 +66,95 @@ pub enum TransactionTrace {
 	/// Matches the formatter used by Blockscout.
 	/// Is also used to built output of OpenEthereum's `trace_filter`.
 	CallList(Vec<Call>),
+	/// Output of geth's `prestateTracer`, by account address.
+	Prestate(BTreeMap<H160, PrestateAccount>),
+	/// Output of OpenEthereum's `stateDiff`, by account address, with the transaction's return
+	/// value.
+	#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+	StateDiff {
+		#[cfg_attr(feature = "std", serde(serialize_with = "bytes_0x_serialize"))]
+		output: Vec<u8>,
+		state_diff: BTreeMap<H160, AccountDiff>,
+	},
+	/// Output of geth's `4byteTracer`: number of calls by function selector and size of the
+	/// arguments.
+	FourByte(
+		#[cfg_attr(feature = "std", serde(serialize_with = "four_byte_serialize"))]
+		BTreeMap<([u8; 4], u32), u64>,
+	),
+	/// Number of times each opcode was executed, by opcode name.
+	OpcodeHistogram(
+		#[cfg_attr(feature = "std", serde(serialize_with = "opcode_histogram_serialize"))]
+		BTreeMap<Vec<u8>, u64>,
+	),
+}
+
+/// State of an account before a transaction, with the storage slots the transaction touched.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PrestateAccount {
+	pub balance: U256,
+
+	#[cfg_attr(feature = "std", serde(serialize_with = "u256_serialize"))]
+	pub nonce: U256,
+
+	#[cfg_attr(
+		feature = "std",
+		serde(serialize_with = "bytes_0x_serialize", skip_serializing_if = "Vec::is_empty")
+	)]
+	pub code: Vec<u8>,
+
+	#[cfg_attr(feature = "std", serde(skip_serializing_if = "BTreeMap::is_empty"))]
+	pub storage: BTreeMap<H256, H256>,
 }
 
+/// Changes a transaction made to an account. Accounts created by the transaction have all their
+/// fields `Born`, and accounts it destroyed all their fields `Died`.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct AccountDiff {
+	pub balance: Diff<U256>,
+	pub nonce: Diff<U256>,
+	pub code: Diff<Bytes>,
+	/// Only the storage slots whose value changed.
+	pub storage: BTreeMap<H256, Diff<H256>>,
+}
+
+/// Change of a value, serialized as OpenEthereum does: `"="`, `{"+": to}`, `{"-": from}` or
+/// `{"*": {"from": from, "to": to}}`.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+pub enum Diff<T> {
+	#[cfg_attr(feature = "std", serde(rename = "="))]
+	Same,
+	#[cfg_attr(feature = "std", serde(rename = "+"))]
+	Born(T),
+	#[cfg_attr(feature = "std", serde(rename = "-"))]
+	Died(T),
+	#[cfg_attr(feature = "std", serde(rename = "*"))]
+	Changed { from: T, to: T },
+}
+
+impl<T: PartialEq> Diff<T> {
+	/// `Same` if the value did not change, `Changed` otherwise.
+	pub fn new(from: T, to: T) -> Self {
+		if from == to {
+			Diff::Same
+		} else {
+			Diff::Changed { from, to }
+		}
+	}
+}
+
+/// Bytes serialized as a `0x` prefixed hex string.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+pub struct Bytes(
+	#[cfg_attr(feature = "std", serde(serialize_with = "bytes_0x_serialize"))] pub Vec<u8>,
+);
+
 #[derive(Clone, Eq, PartialEq, Debug, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Serialize))]
 #[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
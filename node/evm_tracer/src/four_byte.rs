@@ -0,0 +1,84 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::util::*;
+use edgeware_rpc_primitives_debug::single::TransactionTrace;
+use sp_std::collections::btree_map::BTreeMap;
+
+/// Listen to EVM events to count the calls made by a transaction, by function
+/// selector and size of the arguments, matching geth's `4byteTracer`.
+///
+/// Calls whose input is too short to hold a selector are not counted.
+///
+/// # Output example
+///
+/// ```json
+/// {
+///   "0x27dc297e-128": 1,
+///   "0x38cc4831-0": 2,
+///   "0x524f3889-96": 1
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct FourByteTracer {
+	calls: BTreeMap<([u8; 4], u32), u64>,
+}
+
+impl FourByteTracer {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Setup event listeners and execute provided closure.
+	///
+	/// Consume the tracer and return it alongside the return value of
+	/// the closure.
+	pub fn trace<R, F: FnOnce() -> R>(self, f: F) -> (Self, R) {
+		let wrapped = Rc::new(RefCell::new(self));
+
+		let result = {
+			let mut evm = ListenerProxy(Rc::clone(&wrapped));
+
+			// Each line wraps the previous `f` into a `using` call.
+			// Listening to new events results in adding one new line.
+			// Order is irrelevant when registering listeners.
+			let f = || evm_using(&mut evm, f);
+			f()
+		};
+
+		(Rc::try_unwrap(wrapped).unwrap().into_inner(), result)
+	}
+
+	pub fn into_tx_trace(self) -> TransactionTrace {
+		TransactionTrace::FourByte(self.calls)
+	}
+}
+
+impl EvmListener for FourByteTracer {
+	fn event(&mut self, event: EvmEvent) {
+		match event {
+			EvmEvent::Call { input, .. } if input.len() >= 4 => {
+				let mut selector = [0u8; 4];
+				selector.copy_from_slice(&input[..4]);
+				let size = (input.len() - 4) as u32;
+				*self.calls.entry((selector, size)).or_insert(0) += 1;
+			}
+			// We ignore other kinds of message if any (new ones may be added in the future).
+			#[allow(unreachable_patterns)]
+			_ => (),
+		}
+	}
+}
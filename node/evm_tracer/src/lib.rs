@@ -23,9 +23,21 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod call_list;
+mod four_byte;
+#[cfg(test)]
+mod mock;
+mod opcode_histogram;
+mod prestate;
 mod raw;
+mod state_diff;
+#[cfg(test)]
+mod tests;
 mod util;
 
 pub use call_list::CallListTracer;
+pub use four_byte::FourByteTracer;
+pub use opcode_histogram::OpcodeHistogramTracer;
+pub use prestate::PrestateTracer;
 pub use raw::RawTracer;
+pub use state_diff::StateDiffTracer;
 pub use util::EvmListener;
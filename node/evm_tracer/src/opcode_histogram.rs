@@ -0,0 +1,80 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::util::*;
+use edgeware_rpc_primitives_debug::single::TransactionTrace;
+use sp_std::collections::btree_map::BTreeMap;
+
+/// Listen to EVM events to count how many times a transaction executed each
+/// opcode, in all the contexts it created.
+///
+/// # Output example
+///
+/// ```json
+/// {
+///   "ADD": 4,
+///   "CALLDATALOAD": 1,
+///   "PUSH1": 12,
+///   "SSTORE": 1
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct OpcodeHistogramTracer {
+	opcodes: BTreeMap<Vec<u8>, u64>,
+}
+
+impl OpcodeHistogramTracer {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Setup event listeners and execute provided closure.
+	///
+	/// Consume the tracer and return it alongside the return value of
+	/// the closure.
+	pub fn trace<R, F: FnOnce() -> R>(self, f: F) -> (Self, R) {
+		let wrapped = Rc::new(RefCell::new(self));
+
+		let result = {
+			let mut runtime = ListenerProxy(Rc::clone(&wrapped));
+
+			// Each line wraps the previous `f` into a `using` call.
+			// Listening to new events results in adding one new line.
+			// Order is irrelevant when registering listeners.
+			let f = || runtime_using(&mut runtime, f);
+			f()
+		};
+
+		(Rc::try_unwrap(wrapped).unwrap().into_inner(), result)
+	}
+
+	pub fn into_tx_trace(self) -> TransactionTrace {
+		TransactionTrace::OpcodeHistogram(self.opcodes)
+	}
+}
+
+impl RuntimeListener for OpcodeHistogramTracer {
+	fn event(&mut self, event: RuntimeEvent) {
+		match event {
+			RuntimeEvent::Step { opcode, .. } => {
+				*self.opcodes.entry(opcodes_string(opcode)).or_insert(0) += 1;
+			}
+			// We ignore other kinds of message if any (new ones may be added in the future).
+			#[allow(unreachable_patterns)]
+			_ => (),
+		}
+	}
+}
@@ -0,0 +1,163 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::util::*;
+use edgeware_rpc_primitives_debug::single::{PrestateAccount, TransactionTrace};
+use ethereum_types::{H160, H256};
+use sp_std::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
+
+/// Listen to EVM events to record the accounts and storage slots a transaction
+/// touches, and report their state before it ran, matching geth's
+/// `prestateTracer`.
+///
+/// The tracer only records what is touched: `into_tx_trace` reads the state, so
+/// it must be called once the traced transaction's changes have been rolled
+/// back.
+///
+/// # Output example
+///
+/// ```json
+/// {
+///   "0x3ca17a1c4995b95c600275e52da93d2e64dd591f": {
+///     "balance": "0x0",
+///     "nonce": 1,
+///     "code": "0x6080604052348015600f57600080fd5b50",
+///     "storage": {
+///       "0x0000000000000000000000000000000000000000000000000000000000000000":
+///         "0x000000000000000000000000000000000000000000000000000000000000000d"
+///     }
+///   },
+///   "0xfe2882ac0a337a976aa73023c2a2a917f57ba2ed": {
+///     "balance": "0x1bc16d674ec80000",
+///     "nonce": 4
+///   }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct PrestateTracer {
+	// Storage slots touched, by account.
+	// Accounts touched without accessing their storage have no slots.
+	touched: BTreeMap<H160, BTreeSet<H256>>,
+}
+
+impl PrestateTracer {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Setup event listeners and execute provided closure.
+	///
+	/// Consume the tracer and return it alongside the return value of
+	/// the closure.
+	pub fn trace<R, F: FnOnce() -> R>(self, f: F) -> (Self, R) {
+		let wrapped = Rc::new(RefCell::new(self));
+
+		let result = {
+			let mut runtime = ListenerProxy(Rc::clone(&wrapped));
+			let mut evm = ListenerProxy(Rc::clone(&wrapped));
+
+			// Each line wraps the previous `f` into a `using` call.
+			// Listening to new events results in adding one new line.
+			// Order is irrelevant when registering listeners.
+			let f = || runtime_using(&mut runtime, f);
+			let f = || evm_using(&mut evm, f);
+			f()
+		};
+
+		(Rc::try_unwrap(wrapped).unwrap().into_inner(), result)
+	}
+
+	/// Read the current state of the touched accounts from `pallet_evm`.
+	pub fn into_tx_trace<T: pallet_evm::Config>(self) -> TransactionTrace {
+		TransactionTrace::Prestate(
+			self.touched
+				.into_iter()
+				.map(|(address, slots)| {
+					let account = pallet_evm::Pallet::<T>::account_basic(&address);
+					let storage = slots
+						.into_iter()
+						.map(|slot| (slot, pallet_evm::AccountStorages::<T>::get(address, slot)))
+						.collect();
+					let prestate = PrestateAccount {
+						balance: account.balance,
+						nonce: account.nonce,
+						code: pallet_evm::AccountCodes::<T>::get(address),
+						storage,
+					};
+					(address, prestate)
+				})
+				.collect(),
+		)
+	}
+
+	fn touch(&mut self, address: H160) -> &mut BTreeSet<H256> {
+		self.touched.entry(address).or_insert_with(BTreeSet::new)
+	}
+}
+
+impl RuntimeListener for PrestateTracer {
+	fn event(&mut self, event: RuntimeEvent) {
+		match event {
+			RuntimeEvent::SLoad { address, index, .. }
+			| RuntimeEvent::SStore { address, index, .. } => {
+				self.touch(address).insert(index);
+			}
+			// Opcodes reading another account, which is at the top of the stack.
+			// BALANCE, EXTCODESIZE, EXTCODECOPY and EXTCODEHASH.
+			RuntimeEvent::Step { opcode, stack, .. }
+				if matches!(opcode.0, 0x31 | 0x3b | 0x3c | 0x3f) =>
+			{
+				if let Ok(address) = stack.peek(0) {
+					self.touch(H160::from(address));
+				}
+			}
+			// We ignore other kinds of message if any (new ones may be added in the future).
+			#[allow(unreachable_patterns)]
+			_ => (),
+		}
+	}
+}
+
+impl EvmListener for PrestateTracer {
+	fn event(&mut self, event: EvmEvent) {
+		match event {
+			EvmEvent::Call {
+				code_address,
+				context,
+				..
+			} => {
+				self.touch(context.caller);
+				self.touch(context.address);
+				self.touch(code_address);
+			}
+			EvmEvent::Create {
+				caller, address, ..
+			} => {
+				self.touch(caller);
+				self.touch(address);
+			}
+			EvmEvent::Suicide {
+				address, target, ..
+			} => {
+				self.touch(address);
+				self.touch(target);
+			}
+			// We ignore other kinds of message if any (new ones may be added in the future).
+			#[allow(unreachable_patterns)]
+			_ => (),
+		}
+	}
+}
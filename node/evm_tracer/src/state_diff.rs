@@ -0,0 +1,353 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::util::*;
+use edgeware_rpc_primitives_debug::single::{AccountDiff, Bytes, Diff, TransactionTrace};
+use ethereum_types::{H160, H256, U256};
+use evm::{Capture, ExitReason};
+use sp_std::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
+
+/// Listen to EVM events to record the accounts and storage slots a transaction
+/// changes, and report their values before and after it ran, matching
+/// OpenEthereum's `stateDiff`.
+///
+/// Storage writes are kept in a stack of contexts, as `RawTracer` does: the
+/// writes of a context that reverts are dropped with it, and the others are
+/// merged into its parent when it exits.
+///
+/// Values are read from the state rather than from the events, so
+/// `read_final_state` must be called right after the transaction ran, and
+/// `into_tx_trace` once its changes have been rolled back. A slot whose
+/// value ends up unchanged is left out.
+///
+/// # Output example
+///
+/// ```json
+/// {
+///   "output": "0x",
+///   "stateDiff": {
+///     "0x3ca17a1c4995b95c600275e52da93d2e64dd591f": {
+///       "balance": "=",
+///       "nonce": "=",
+///       "code": "=",
+///       "storage": {
+///         "0x0000000000000000000000000000000000000000000000000000000000000000": {
+///           "*": {
+///             "from": "0x000000000000000000000000000000000000000000000000000000000000000d",
+///             "to": "0x000000000000000000000000000000000000000000000000000000000000000e"
+///           }
+///         }
+///       }
+///     },
+///     "0xfe2882ac0a337a976aa73023c2a2a917f57ba2ed": {
+///       "balance": { "*": { "from": "0x1bc16d674ec80000", "to": "0x1bc16d2e6a5d1800" } },
+///       "nonce": { "*": { "from": "0x4", "to": "0x5" } },
+///       "code": "=",
+///       "storage": {}
+///     }
+///   }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct StateDiffTracer {
+	return_value: Vec<u8>,
+
+	new_context: bool,
+	context_stack: Vec<Context>,
+
+	// Accounts touched by calls, creations and self-destructs.
+	accounts: BTreeSet<H160>,
+	// Storage slots written by contexts that did not revert, by account.
+	written: BTreeMap<H160, BTreeSet<H256>>,
+	// State of the touched accounts after the transaction.
+	final_state: BTreeMap<H160, AccountState>,
+}
+
+#[derive(Debug, Default)]
+struct Context {
+	// Storage slots written in this context or its exited children, by account.
+	written: BTreeMap<H160, BTreeSet<H256>>,
+}
+
+#[derive(Debug, Default)]
+struct AccountState {
+	balance: U256,
+	nonce: U256,
+	code: Vec<u8>,
+	storage: BTreeMap<H256, H256>,
+}
+
+impl AccountState {
+	fn read<T: pallet_evm::Config>(address: H160, slots: &BTreeSet<H256>) -> Self {
+		let account = pallet_evm::Pallet::<T>::account_basic(&address);
+		AccountState {
+			balance: account.balance,
+			nonce: account.nonce,
+			code: pallet_evm::AccountCodes::<T>::get(address),
+			storage: slots
+				.iter()
+				.map(|slot| (*slot, pallet_evm::AccountStorages::<T>::get(address, *slot)))
+				.collect(),
+		}
+	}
+
+	fn exists(&self) -> bool {
+		!self.balance.is_zero() || !self.nonce.is_zero() || !self.code.is_empty()
+	}
+}
+
+impl StateDiffTracer {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Setup event listeners and execute provided closure.
+	///
+	/// Consume the tracer and return it alongside the return value of
+	/// the closure.
+	pub fn trace<R, F: FnOnce() -> R>(self, f: F) -> (Self, R) {
+		let wrapped = Rc::new(RefCell::new(self));
+
+		let result = {
+			let mut gasometer = ListenerProxy(Rc::clone(&wrapped));
+			let mut runtime = ListenerProxy(Rc::clone(&wrapped));
+			let mut evm = ListenerProxy(Rc::clone(&wrapped));
+
+			// Each line wraps the previous `f` into a `using` call.
+			// Listening to new events results in adding one new line.
+			// Order is irrelevant when registering listeners.
+			let f = || runtime_using(&mut runtime, f);
+			let f = || gasometer_using(&mut gasometer, f);
+			let f = || evm_using(&mut evm, f);
+			f()
+		};
+
+		(Rc::try_unwrap(wrapped).unwrap().into_inner(), result)
+	}
+
+	/// Read the state of the touched accounts after the transaction from `pallet_evm`.
+	pub fn read_final_state<T: pallet_evm::Config>(&mut self) {
+		// Contexts left on the stack never emitted their exit, as happens for
+		// calls to precompiles. Their slots are kept: the values read tell
+		// whether the writes were kept.
+		for context in sp_std::mem::take(&mut self.context_stack) {
+			merge(&mut self.written, context.written);
+		}
+
+		let empty = BTreeSet::new();
+		self.final_state = self
+			.touched()
+			.map(|address| {
+				let slots = self.written.get(&address).unwrap_or(&empty);
+				(address, AccountState::read::<T>(address, slots))
+			})
+			.collect();
+	}
+
+	/// Read the state of the touched accounts before the transaction from `pallet_evm`,
+	/// and compare it with the state read by `read_final_state`.
+	pub fn into_tx_trace<T: pallet_evm::Config>(self) -> TransactionTrace {
+		let empty = BTreeSet::new();
+		let mut state_diff = BTreeMap::new();
+		for (address, to) in self.final_state {
+			let slots = self.written.get(&address).unwrap_or(&empty);
+			let from = AccountState::read::<T>(address, slots);
+			if let Some(diff) = account_diff(from, to) {
+				state_diff.insert(address, diff);
+			}
+		}
+
+		TransactionTrace::StateDiff {
+			output: self.return_value,
+			state_diff,
+		}
+	}
+
+	fn touched(&self) -> impl Iterator<Item = H160> + '_ {
+		self.accounts
+			.iter()
+			.chain(self.written.keys())
+			.copied()
+			.collect::<BTreeSet<_>>()
+			.into_iter()
+	}
+}
+
+fn merge(into: &mut BTreeMap<H160, BTreeSet<H256>>, from: BTreeMap<H160, BTreeSet<H256>>) {
+	for (address, mut slots) in from {
+		into.entry(address)
+			.or_insert_with(BTreeSet::new)
+			.append(&mut slots);
+	}
+}
+
+/// The changes from `from` to `to`, or `None` if there are none.
+fn account_diff(from: AccountState, to: AccountState) -> Option<AccountDiff> {
+	let diff = match (from.exists(), to.exists()) {
+		(false, false) => return None,
+		(false, true) => AccountDiff {
+			balance: Diff::Born(to.balance),
+			nonce: Diff::Born(to.nonce),
+			code: Diff::Born(Bytes(to.code)),
+			storage: to
+				.storage
+				.into_iter()
+				.filter(|(_, value)| !value.is_zero())
+				.map(|(slot, value)| (slot, Diff::Born(value)))
+				.collect(),
+		},
+		(true, false) => AccountDiff {
+			balance: Diff::Died(from.balance),
+			nonce: Diff::Died(from.nonce),
+			code: Diff::Died(Bytes(from.code)),
+			storage: from
+				.storage
+				.into_iter()
+				.filter(|(_, value)| !value.is_zero())
+				.map(|(slot, value)| (slot, Diff::Died(value)))
+				.collect(),
+		},
+		(true, true) => {
+			let mut to_storage = to.storage;
+			AccountDiff {
+				balance: Diff::new(from.balance, to.balance),
+				nonce: Diff::new(from.nonce, to.nonce),
+				code: Diff::new(Bytes(from.code), Bytes(to.code)),
+				storage: from
+					.storage
+					.into_iter()
+					.filter_map(|(slot, from)| {
+						let to = to_storage.remove(&slot).unwrap_or_default();
+						match Diff::new(from, to) {
+							Diff::Same => None,
+							diff => Some((slot, diff)),
+						}
+					})
+					.collect(),
+			}
+		}
+	};
+
+	let unchanged = diff.balance == Diff::Same
+		&& diff.nonce == Diff::Same
+		&& diff.code == Diff::Same
+		&& diff.storage.is_empty();
+	if unchanged {
+		None
+	} else {
+		Some(diff)
+	}
+}
+
+impl GasometerListener for StateDiffTracer {
+	fn event(&mut self, event: GasometerEvent) {
+		match event {
+			GasometerEvent::RecordTransaction { .. } => {
+				// First event of a transaction.
+				// Next step will be the first context.
+				self.new_context = true;
+			}
+			// We ignore other kinds of message if any (new ones may be added in the future).
+			#[allow(unreachable_patterns)]
+			_ => (),
+		}
+	}
+}
+
+impl RuntimeListener for StateDiffTracer {
+	fn event(&mut self, event: RuntimeEvent) {
+		match event {
+			RuntimeEvent::Step { .. } => {
+				// Create a context if needed.
+				if self.new_context {
+					self.new_context = false;
+					self.context_stack.push(Context::default());
+				}
+			}
+			RuntimeEvent::StepResult {
+				result,
+				return_value,
+			} => match result {
+				Err(Capture::Exit(reason)) => {
+					// Exit = we exit the context (should always be some)
+					if let Some(context) = self.context_stack.pop() {
+						// If final context is exited, we store the return value.
+						if self.context_stack.is_empty() {
+							self.return_value = return_value.to_vec();
+						}
+
+						// Writes of a reverted context are dropped, others go to the parent.
+						if matches!(reason, &ExitReason::Succeed(_)) {
+							match self.context_stack.last_mut() {
+								Some(parent_context) => {
+									merge(&mut parent_context.written, context.written)
+								}
+								None => merge(&mut self.written, context.written),
+							}
+						}
+					}
+				}
+				Err(Capture::Trap(opcode)) if ContextType::from(*opcode).is_some() => {
+					self.new_context = true;
+				}
+				_ => (),
+			},
+			RuntimeEvent::SStore { address, index, .. } => {
+				if let Some(context) = self.context_stack.last_mut() {
+					context
+						.written
+						.entry(address)
+						.or_insert_with(BTreeSet::new)
+						.insert(index);
+				}
+			}
+			// We ignore other kinds of message if any (new ones may be added in the future).
+			#[allow(unreachable_patterns)]
+			_ => (),
+		}
+	}
+}
+
+impl EvmListener for StateDiffTracer {
+	fn event(&mut self, event: EvmEvent) {
+		match event {
+			EvmEvent::Call {
+				code_address,
+				context,
+				..
+			} => {
+				self.accounts.insert(context.caller);
+				self.accounts.insert(context.address);
+				self.accounts.insert(code_address);
+			}
+			EvmEvent::Create {
+				caller, address, ..
+			} => {
+				self.accounts.insert(caller);
+				self.accounts.insert(address);
+			}
+			EvmEvent::Suicide {
+				address, target, ..
+			} => {
+				self.accounts.insert(address);
+				self.accounts.insert(target);
+			}
+			// We ignore other kinds of message if any (new ones may be added in the future).
+			#[allow(unreachable_patterns)]
+			_ => (),
+		}
+	}
+}
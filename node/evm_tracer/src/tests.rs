@@ -0,0 +1,279 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Feed the tracers synthetic EVM events and check the JSON they produce.
+
+use crate::{
+	mock::*, util::*, FourByteTracer, OpcodeHistogramTracer, PrestateTracer, StateDiffTracer,
+};
+use ethereum_types::{H160, H256, U256};
+use evm::{
+	gasometer::Snapshot, Capture, Context, ExitReason, ExitRevert, ExitSucceed, Memory, Stack, Trap,
+};
+use frame_support::{
+	storage::{with_transaction, TransactionOutcome},
+	traits::Currency,
+};
+use serde_json::json;
+
+const CALLER: H160 = H160([0x11; 20]);
+const CONTRACT: H160 = H160([0x22; 20]);
+const OTHER: H160 = H160([0x33; 20]);
+
+fn slot(index: u64) -> H256 {
+	H256::from_low_u64_be(index)
+}
+
+fn call(tracer: &mut impl EvmListener, caller: H160, address: H160, input: &[u8]) {
+	let context = Context {
+		address,
+		caller,
+		apparent_value: U256::zero(),
+	};
+	tracer.event(EvmEvent::Call {
+		code_address: address,
+		transfer: &None,
+		input,
+		target_gas: None,
+		is_static: false,
+		context: &context,
+	});
+}
+
+/// Step through `opcode`, with `stack_top` at the top of the stack.
+fn step(tracer: &mut impl RuntimeListener, opcode: Opcode, stack_top: Option<H256>) {
+	let context = Context {
+		address: H160::zero(),
+		caller: H160::zero(),
+		apparent_value: U256::zero(),
+	};
+	let mut stack = Stack::new(1024);
+	if let Some(value) = stack_top {
+		stack.push(value).unwrap();
+	}
+	tracer.event(RuntimeEvent::Step {
+		context: &context,
+		opcode,
+		position: &Ok(0),
+		stack: &stack,
+		memory: &Memory::new(0),
+	});
+}
+
+/// The first event of a transaction.
+fn record_transaction(tracer: &mut impl GasometerListener) {
+	tracer.event(GasometerEvent::RecordTransaction {
+		cost: 21_000,
+		snapshot: Snapshot {
+			gas_limit: 100_000,
+			memory_gas: 0,
+			used_gas: 0,
+			refunded_gas: 0,
+		},
+	});
+}
+
+fn step_result(
+	tracer: &mut impl RuntimeListener,
+	result: Result<(), Capture<ExitReason, Trap>>,
+	return_value: &[u8],
+) {
+	tracer.event(RuntimeEvent::StepResult {
+		result: &result,
+		return_value,
+	});
+}
+
+fn sstore(tracer: &mut impl RuntimeListener, address: H160, index: H256, value: H256) {
+	tracer.event(RuntimeEvent::SStore {
+		address,
+		index,
+		value,
+	});
+}
+
+#[test]
+fn prestate_reports_the_state_before_the_rolled_back_transaction() {
+	new_test_ext().execute_with(|| {
+		set_account(CALLER, 100, 1, vec![], &[]);
+		set_account(CONTRACT, 0, 1, vec![0x60, 0x00], &[(slot(0), slot(1))]);
+
+		let tracer = with_transaction(|| {
+			let mut tracer = PrestateTracer::new();
+			call(&mut tracer, CALLER, CONTRACT, &[]);
+			sstore(&mut tracer, CONTRACT, slot(0), slot(2));
+			// BALANCE of an account that does not exist.
+			step(&mut tracer, Opcode(0x31), Some(H256::from(OTHER)));
+
+			// The changes of the transaction, rolled back below.
+			Balances::make_free_balance_be(&account_id(CALLER), 10);
+			System::inc_account_nonce(&account_id(CALLER));
+			pallet_evm::AccountStorages::<Test>::insert(CONTRACT, slot(0), slot(2));
+
+			TransactionOutcome::Rollback(tracer)
+		});
+
+		assert_eq!(
+			serde_json::to_value(tracer.into_tx_trace::<Test>()).unwrap(),
+			json!({
+				"0x1111111111111111111111111111111111111111": {
+					"balance": "0x64",
+					"nonce": 1,
+				},
+				"0x2222222222222222222222222222222222222222": {
+					"balance": "0x0",
+					"nonce": 1,
+					"code": "0x6000",
+					"storage": {
+						"0x0000000000000000000000000000000000000000000000000000000000000000":
+							"0x0000000000000000000000000000000000000000000000000000000000000001",
+					},
+				},
+				"0x3333333333333333333333333333333333333333": {
+					"balance": "0x0",
+					"nonce": 0,
+				},
+			})
+		);
+	});
+}
+
+#[test]
+fn state_diff_leaves_out_the_writes_of_a_reverted_call() {
+	new_test_ext().execute_with(|| {
+		set_account(CALLER, 100, 1, vec![], &[]);
+		set_account(CONTRACT, 0, 1, vec![0x60, 0x00], &[(slot(0), slot(1))]);
+		set_account(OTHER, 0, 1, vec![0x60, 0x00], &[(slot(0), slot(1))]);
+
+		let tracer = with_transaction(|| {
+			let mut tracer = StateDiffTracer::new();
+			record_transaction(&mut tracer);
+			call(&mut tracer, CALLER, CONTRACT, &[]);
+			step(&mut tracer, Opcode::SSTORE, None);
+			sstore(&mut tracer, CONTRACT, slot(0), slot(2));
+			pallet_evm::AccountStorages::<Test>::insert(CONTRACT, slot(0), slot(2));
+
+			// CONTRACT calls OTHER, which writes to its storage and reverts.
+			step(&mut tracer, Opcode::CALL, None);
+			step_result(&mut tracer, Err(Capture::Trap(Opcode::CALL)), &[]);
+			call(&mut tracer, CONTRACT, OTHER, &[]);
+			step(&mut tracer, Opcode::SSTORE, None);
+			sstore(&mut tracer, OTHER, slot(0), slot(2));
+			step(&mut tracer, Opcode::REVERT, None);
+			step_result(
+				&mut tracer,
+				Err(Capture::Exit(ExitRevert::Reverted.into())),
+				&[],
+			);
+
+			step(&mut tracer, Opcode::RETURN, None);
+			step_result(
+				&mut tracer,
+				Err(Capture::Exit(ExitSucceed::Returned.into())),
+				&[1],
+			);
+
+			// The caller pays for the transaction.
+			Balances::make_free_balance_be(&account_id(CALLER), 90);
+			System::inc_account_nonce(&account_id(CALLER));
+
+			tracer.read_final_state::<Test>();
+			TransactionOutcome::Rollback(tracer)
+		});
+
+		assert_eq!(
+			serde_json::to_value(tracer.into_tx_trace::<Test>()).unwrap(),
+			json!({
+				"output": "0x01",
+				"stateDiff": {
+					"0x1111111111111111111111111111111111111111": {
+						"balance": { "*": { "from": "0x64", "to": "0x5a" } },
+						"nonce": { "*": { "from": "0x1", "to": "0x2" } },
+						"code": "=",
+						"storage": {},
+					},
+					"0x2222222222222222222222222222222222222222": {
+						"balance": "=",
+						"nonce": "=",
+						"code": "=",
+						"storage": {
+							"0x0000000000000000000000000000000000000000000000000000000000000000": {
+								"*": {
+									"from": "0x0000000000000000000000000000000000000000000000000000000000000001",
+									"to": "0x0000000000000000000000000000000000000000000000000000000000000002",
+								},
+							},
+						},
+					},
+				},
+			})
+		);
+	});
+}
+
+#[test]
+fn four_byte_counts_calls_by_selector_and_size() {
+	let transfer = [&[0xa9, 0x05, 0x9c, 0xbb][..], &[0; 64]].concat();
+	let balance_of = [&[0x70, 0xa0, 0x82, 0x31][..], &[0; 32]].concat();
+
+	let mut tracer = FourByteTracer::new();
+	call(&mut tracer, CALLER, CONTRACT, &transfer);
+	call(&mut tracer, CONTRACT, OTHER, &balance_of);
+	call(&mut tracer, CONTRACT, OTHER, &transfer);
+	call(&mut tracer, CONTRACT, OTHER, &transfer[..4]);
+	// Too short to hold a selector.
+	call(&mut tracer, CONTRACT, OTHER, &[]);
+	call(&mut tracer, CONTRACT, OTHER, &transfer[..3]);
+
+	assert_eq!(
+		serde_json::to_value(tracer.into_tx_trace()).unwrap(),
+		json!({
+			"0x70a08231-32": 1,
+			"0xa9059cbb-0": 1,
+			"0xa9059cbb-64": 2,
+		})
+	);
+}
+
+#[test]
+fn opcode_histogram_counts_the_steps_of_all_contexts() {
+	let mut tracer = OpcodeHistogramTracer::new();
+	step(&mut tracer, Opcode::PUSH1, None);
+	step(&mut tracer, Opcode::PUSH1, None);
+	step(&mut tracer, Opcode::CALL, None);
+	step_result(&mut tracer, Err(Capture::Trap(Opcode::CALL)), &[]);
+	// The called context.
+	step(&mut tracer, Opcode::PUSH1, None);
+	step(&mut tracer, Opcode::SSTORE, None);
+	step(&mut tracer, Opcode::STOP, None);
+	step_result(
+		&mut tracer,
+		Err(Capture::Exit(ExitSucceed::Stopped.into())),
+		&[],
+	);
+	step(&mut tracer, Opcode::ADD, None);
+
+	assert_eq!(
+		serde_json::to_value(tracer.into_tx_trace()).unwrap(),
+		json!({
+			"ADD": 1,
+			"CALL": 1,
+			"PUSH1": 3,
+			"SSTORE": 1,
+			"STOP": 1,
+		})
+	);
+}
@@ -137,7 +137,7 @@ pub struct FullDeps<C, P, SC, B, A: ChainApi> {
 	pub max_past_logs: u32,
 	/// The list of optional RPC extensions.
 	pub ethapi_cmd: Vec<EthApiCmd>,
-	/// Debug server requester.
+	/// Debug server requester, also used by `trace_replayTransaction`.
 	pub debug_requester: Option<DebugRequester>,
 	/// Trace filter cache server requester.
 	pub trace_filter_requester: Option<TraceFilterCacheRequester>,
@@ -158,6 +158,7 @@ where
 	C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber, Hash>,
 	C::Api: BlockBuilder<Block>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: edge_treasury_reward_rpc::TreasuryRewardRuntimeApi<Block, Balance, BlockNumber>,
 	C::Api: fp_rpc::EthereumRuntimeRPCApi<Block>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool<Block = Block> + 'static,
@@ -172,6 +173,7 @@ where
 		EthSigner, HexEncodedIdProvider, NetApi, NetApiServer, Web3Api, Web3ApiServer,
 	};
 	use pallet_contracts_rpc::{Contracts, ContractsApi};
+	use edge_treasury_reward_rpc::{TreasuryReward, TreasuryRewardApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
 
@@ -215,6 +217,7 @@ where
 	io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(
 		client.clone(),
 	)));
+	io.extend_with(TreasuryRewardApi::to_delegate(TreasuryReward::new(client.clone())));
 
 	let mut signers = Vec::new();
 	if enable_dev_signer {
@@ -292,12 +295,15 @@ where
 		io.extend_with(TraceServer::to_delegate(Trace::new(
 			client,
 			trace_filter_requester,
+			debug_requester.clone().expect("the debug task is spawned along the trace one; qed"),
 			trace_filter_max_count,
 		)));
 	}
 
-	if let Some(debug_requester) = debug_requester {
-		io.extend_with(DebugServer::to_delegate(Debug::new(debug_requester)));
+	if ethapi_cmd.contains(&EthApiCmd::Debug) {
+		if let Some(debug_requester) = debug_requester {
+			io.extend_with(DebugServer::to_delegate(Debug::new(debug_requester)));
+		}
 	}
 
 	io
@@ -387,7 +393,10 @@ where
 			(None, None)
 		};
 
-	let (debug_task, debug_requester) = if rpc_config.ethapi.contains(&EthApiCmd::Debug) {
+	// `trace_replayTransaction` replays transactions with the debug task.
+	let (debug_task, debug_requester) = if rpc_config.ethapi.contains(&EthApiCmd::Debug)
+		|| rpc_config.ethapi.contains(&EthApiCmd::Trace)
+	{
 		let (debug_task, debug_requester) = DebugHandler::task(
 			Arc::clone(&params.client),
 			Arc::clone(&params.substrate_backend),
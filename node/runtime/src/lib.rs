@@ -101,11 +101,15 @@ pub mod impls;
 use impls::Author;
 
 pub mod precompiles;
-pub use precompiles::EdgewarePrecompiles;
+pub use precompiles::{EdgewarePrecompiles, EvmExtension, PrecompileSourceRunner};
 
 /// Constant values used within the runtime.
 pub mod constants;
 use constants::{currency::*, time::*};
+
+/// Runtime storage migrations.
+pub mod migrations;
+
 use pallet_contracts::weights::WeightInfo;
 use sp_runtime::generic::Era;
 
@@ -134,8 +138,8 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to equal spec_version. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 51,
-	impl_version: 51,
+	spec_version: 52,
+	impl_version: 52,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 2,
 };
@@ -149,8 +153,8 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to equal spec_version. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 10052,
-	impl_version: 10052,
+	spec_version: 10053,
+	impl_version: 10053,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 2,
 };
@@ -329,6 +333,25 @@ impl InstanceFilter<Call> for ProxyType {
 	}
 }
 
+/// The calls EVM contracts may dispatch through the Dispatch precompile.
+/// `Utility` batches are allowed if every call in them is.
+#[derive(Copy, Clone, Default, RuntimeDebug)]
+pub struct EvmDispatchFilter;
+
+impl InstanceFilter<Call> for EvmDispatchFilter {
+	fn filter(&self, c: &Call) -> bool {
+		matches!(
+			c,
+			Call::System(frame_system::Call::remark(..))
+				| Call::Democracy(..)
+				| Call::Council(..)
+				| Call::PhragmenElection(..)
+				| Call::Treasury(..)
+				| Call::Identity(..)
+		)
+	}
+}
+
 impl pallet_proxy::Config for Runtime {
 	type AnnouncementDepositBase = AnnouncementDepositBase;
 	type AnnouncementDepositFactor = AnnouncementDepositFactor;
@@ -1001,7 +1024,7 @@ parameter_types! {
 
 impl pallet_contracts::Config for Runtime {
 	type CallStack = [pallet_contracts::Frame<Self>; 31];
-	type ChainExtension = ();
+	type ChainExtension = EvmExtension<Self>;
 	type Currency = Balances;
 	type DeletionQueueDepth = DeletionQueueDepth;
 	type DeletionWeightLimit = DeletionWeightLimit;
@@ -1111,7 +1134,7 @@ impl pallet_evm::Config for Runtime {
 	type GasWeightMapping = EdgewareGasWeightMapping;
 	type OnChargeTransaction = ();
 	type Precompiles = EdgewarePrecompiles<Self>;
-	type Runner = pallet_evm::runner::stack::Runner<Self>;
+	type Runner = PrecompileSourceRunner<Self>;
 	type WithdrawOrigin = EnsureAddressTruncated;
 
 	/// EVM config used in the module.
@@ -1149,9 +1172,35 @@ impl pallet_dynamic_fee::Config for Runtime {
 	type MinGasPriceBoundDivisor = BoundDivision;
 }
 
+impl evm_allowances::Config for Runtime {}
+
+impl evm_dispatch::Config for Runtime {
+	type CallFilter = EvmDispatchFilter;
+	type Event = Event;
+}
+
+parameter_types! {
+	pub const BlocksPerYear: BlockNumber = 365 * DAYS;
+	pub const MaxRewardRecipients: u32 = 16;
+	pub const MaxRewardCurvePoints: u32 = 100;
+	pub const MaxRewardAdjustment: Permill = Permill::from_percent(10);
+}
+
 impl treasury_reward::Config for Runtime {
+	type BlocksPerYear = BlocksPerYear;
 	type Currency = Balances;
 	type Event = Event;
+	type MaxAdjustment = MaxRewardAdjustment;
+	type MaxCurvePoints = MaxRewardCurvePoints;
+	type MaxRecipients = MaxRewardRecipients;
+	/// A super-majority of the council can adjust the payout within
+	/// `MaxAdjustment`.
+	type UpdateOrigin = EnsureOneOf<
+		AccountId,
+		EnsureRoot<AccountId>,
+		pallet_collective::EnsureProportionAtLeast<_3, _4, AccountId, CouncilCollective>,
+	>;
+	type WeightInfo = treasury_reward::weights::SubstrateWeight<Runtime>;
 }
 
 construct_runtime!(
@@ -1202,6 +1251,8 @@ construct_runtime!(
 		// REMOVED: Currencies: webb_currencies::{Pallet, Call, Storage, Event<T>} = 42,
 		// REMOVED: NonFungibleTokenModule: orml_nft::{Pallet, Storage, Config<T>} = 43,
 		// REMOVED: NFT: nft::{Pallet, Call, Event<T>} = 44,
+		EvmAllowances: evm_allowances::{Pallet, Storage} = 45,
+		EvmDispatch: evm_dispatch::{Pallet, Event<T>} = 46,
 	}
 );
 
@@ -1260,6 +1311,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPallets,
+	migrations::Migrations,
 >;
 
 pub type Extrinsic = <Block as BlockT>::Extrinsic;
@@ -1425,6 +1477,12 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl treasury_reward_rpc_runtime_api::TreasuryRewardApi<Block, Balance, BlockNumber> for Runtime {
+		fn projection(horizon: BlockNumber) -> treasury_reward::MintingProjection<Balance, BlockNumber> {
+			TreasuryReward::projection(horizon)
+		}
+	}
+
 	impl sp_session::SessionKeys<Block> for Runtime {
 		fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
 			SessionKeys::generate(seed)
@@ -1566,7 +1624,11 @@ impl_runtime_apis! {
 			sp_runtime::DispatchError
 		> {
 			use edgeware_rpc_primitives_debug::single::TraceType;
-			use edgeware_evm_tracer::{RawTracer, CallListTracer};
+			use edgeware_evm_tracer::{
+				RawTracer, CallListTracer, PrestateTracer, StateDiffTracer, FourByteTracer,
+				OpcodeHistogramTracer,
+			};
+			use sp_runtime::TransactionOutcome;
 
 			// Apply the a subset of extrinsics: all the substrate-specific or ethereum transactions
 			// that preceded the requested transaction.
@@ -1594,7 +1656,43 @@ impl_runtime_apis! {
 										.0
 										.into_tx_trace()
 									)
+								},
+								TraceType::Prestate => {
+									// Roll the transaction back to read the state it started from.
+									let tracer = frame_support::storage::with_transaction(|| {
+										TransactionOutcome::Rollback(PrestateTracer::new()
+											.trace(|| Executive::apply_extrinsic(ext))
+											.0
+										)
+									});
+									Ok(tracer.into_tx_trace::<Runtime>())
+								}
+								TraceType::StateDiff => {
+									// Read the state the transaction left, then roll it back to
+									// read the state it started from.
+									let tracer = frame_support::storage::with_transaction(|| {
+										let mut tracer = StateDiffTracer::new()
+											.trace(|| Executive::apply_extrinsic(ext))
+											.0;
+										tracer.read_final_state::<Runtime>();
+										TransactionOutcome::Rollback(tracer)
+									});
+									Ok(tracer.into_tx_trace::<Runtime>())
 								}
+								TraceType::FourByte => {
+									Ok(FourByteTracer::new()
+										.trace(|| Executive::apply_extrinsic(ext))
+										.0
+										.into_tx_trace()
+									)
+								},
+								TraceType::OpcodeHistogram => {
+									Ok(OpcodeHistogramTracer::new()
+										.trace(|| Executive::apply_extrinsic(ext))
+										.0
+										.into_tx_trace()
+									)
+								},
 							}
 
 						} else {
@@ -1617,7 +1715,7 @@ impl_runtime_apis! {
 				edgeware_rpc_primitives_debug::block::TransactionTrace>,
 				sp_runtime::DispatchError
 			> {
-			use edgeware_rpc_primitives_debug::{single, block, CallResult, CreateResult, CreateType};
+			use edgeware_rpc_primitives_debug::{single, block};
 			use edgeware_evm_tracer::CallListTracer;
 
 			let mut config = <Runtime as pallet_evm::Config>::config().clone();
@@ -1641,101 +1739,10 @@ impl_runtime_apis! {
 						};
 
 						// Convert traces from "single" format to "block" format.
-						let mut tx_traces: Vec<_> = tx_traces.into_iter().map(|trace|
-							match trace.inner {
-								single::CallInner::Call {
-									input, to, res, call_type
-								} => block::TransactionTrace {
-									action: block::TransactionTraceAction::Call {
-										call_type,
-										from: trace.from,
-										gas: trace.gas,
-										input,
-										to,
-										value: trace.value,
-									},
-									// Can't be known here, must be inserted upstream.
-									block_hash: H256::default(),
-									// Can't be known here, must be inserted upstream.
-									block_number: 0,
-									output: match res {
-										CallResult::Output(output) => {
-											block::TransactionTraceOutput::Result(
-												block::TransactionTraceResult::Call {
-													gas_used: trace.gas_used,
-													output
-												})
-										},
-										CallResult::Error(error) =>
-											block::TransactionTraceOutput::Error(error),
-									},
-									subtraces: trace.subtraces,
-									trace_address: trace.trace_address,
-									// Can't be known here, must be inserted upstream.
-									transaction_hash: H256::default(),
-									transaction_position: eth_tx_index,
-								},
-								single::CallInner::Create { init, res } => block::TransactionTrace {
-									action: block::TransactionTraceAction::Create {
-										creation_method: CreateType::Create,
-										from: trace.from,
-										gas: trace.gas,
-										init,
-										value: trace.value,
-									},
-									// Can't be known here, must be inserted upstream.
-									block_hash: H256::default(),
-									// Can't be known here, must be inserted upstream.
-									block_number: 0,
-									output: match res {
-										CreateResult::Success {
-											created_contract_address_hash,
-											created_contract_code
-										} => {
-											block::TransactionTraceOutput::Result(
-												block::TransactionTraceResult::Create {
-													gas_used: trace.gas_used,
-													code: created_contract_code,
-													address: created_contract_address_hash,
-												}
-											)
-										},
-										CreateResult::Error {
-											error
-										} => block::TransactionTraceOutput::Error(error),
-									},
-									subtraces: trace.subtraces,
-									trace_address: trace.trace_address,
-									// Can't be known here, must be inserted upstream.
-									transaction_hash: H256::default(),
-									transaction_position: eth_tx_index,
-
-								},
-								single::CallInner::SelfDestruct {
-									balance,
-									refund_address
-								} => block::TransactionTrace {
-									action: block::TransactionTraceAction::Suicide {
-										address: trace.from,
-										balance,
-										refund_address,
-									},
-									// Can't be known here, must be inserted upstream.
-									block_hash: H256::default(),
-									// Can't be known here, must be inserted upstream.
-									block_number: 0,
-									output: block::TransactionTraceOutput::Result(
-												block::TransactionTraceResult::Suicide
-											),
-									subtraces: trace.subtraces,
-									trace_address: trace.trace_address,
-									// Can't be known here, must be inserted upstream.
-									transaction_hash: H256::default(),
-									transaction_position: eth_tx_index,
-
-								},
-							}
-						).collect();
+						let mut tx_traces: Vec<_> = tx_traces
+							.into_iter()
+							.map(|trace| block::TransactionTrace::from_call(trace, eth_tx_index))
+							.collect();
 
 						traces.append(&mut tx_traces);
 
@@ -1768,6 +1775,14 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[cfg(feature = "try-runtime")]
+	impl frame_try_runtime::TryRuntime<Block> for Runtime {
+		fn on_runtime_upgrade() -> Result<(Weight, Weight), sp_runtime::RuntimeString> {
+			let weight = Executive::try_runtime_upgrade()?;
+			Ok((weight, RuntimeBlockWeights::get().max_block))
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -1795,6 +1810,7 @@ impl_runtime_apis! {
 			let params = (&config, &whitelist);
 
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
+			add_benchmark!(params, batches, treasury_reward, TreasuryReward);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)
@@ -0,0 +1,80 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage migrations run by `Executive` on runtime upgrades.
+//!
+//! Migrations are one-shot: once the upgrade that ran a migration has been
+//! enacted on every live chain, remove it from `Migrations`.
+
+use crate::{
+	precompiles::{EdgewarePrecompiles, PRECOMPILE_CODE},
+	Runtime, EVM,
+};
+use frame_support::{
+	traits::{Get, OnRuntimeUpgrade},
+	weights::Weight,
+};
+
+/// Moves treasury_reward storage to `V2_0_0`.
+pub struct TreasuryRewardV2;
+
+impl OnRuntimeUpgrade for TreasuryRewardV2 {
+	fn on_runtime_upgrade() -> Weight {
+		treasury_reward::migrations::v2::migrate::<Runtime>()
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		treasury_reward::migrations::v2::pre_migrate::<Runtime>()
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		treasury_reward::migrations::v2::post_migrate::<Runtime>()
+	}
+}
+
+/// Stores `PRECOMPILE_CODE` at every precompile address that does not hold it,
+/// as genesis does for new chains.
+pub struct PrecompileCode;
+
+impl OnRuntimeUpgrade for PrecompileCode {
+	fn on_runtime_upgrade() -> Weight {
+		let mut reads = 0;
+		let mut writes = 0;
+		for address in EdgewarePrecompiles::<Runtime>::used_addresses() {
+			reads += 1;
+			if EVM::account_codes(address) != PRECOMPILE_CODE {
+				pallet_evm::AccountCodes::<Runtime>::insert(address, PRECOMPILE_CODE.to_vec());
+				writes += 1;
+			}
+		}
+		<Runtime as frame_system::Config>::DbWeight::get().reads_writes(reads, writes)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		if EdgewarePrecompiles::<Runtime>::used_addresses()
+			.any(|address| EVM::account_codes(address) != PRECOMPILE_CODE)
+		{
+			return Err("a precompile address is missing its code");
+		}
+		Ok(())
+	}
+}
+
+/// The migrations to run on the next runtime upgrade, in order.
+pub type Migrations = (TreasuryRewardV2, PrecompileCode);
@@ -1,32 +1,102 @@
 use codec::Decode;
 use evm::{executor::PrecompileOutput, Context, ExitError};
-use frame_support::dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo};
-use pallet_evm::{Precompile, PrecompileSet};
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	traits::GetCallMetadata,
+};
+#[cfg(feature = "std")]
+use pallet_evm::GenesisAccount;
+use pallet_evm::{runner::stack::Runner as StackRunner, CallInfo, CreateInfo, Precompile, PrecompileSet, Runner};
 use pallet_evm_precompile_blake2::Blake2F;
 use pallet_evm_precompile_bn128::{Bn128Add, Bn128Mul, Bn128Pairing};
 use pallet_evm_precompile_curve25519::{Curve25519Add, Curve25519ScalarMul};
-use pallet_evm_precompile_dispatch::Dispatch;
 use pallet_evm_precompile_ed25519::Ed25519Verify;
 use pallet_evm_precompile_modexp::Modexp;
 use pallet_evm_precompile_sha3fips::Sha3FIPS256;
 use pallet_evm_precompile_simple::{ECRecover, ECRecoverPublicKey, Identity, Ripemd160, Sha256};
-use sp_core::H160;
+use sp_core::{crypto::UncheckedFrom, H160, H256, U256};
+use sp_runtime::DispatchError;
 
-use sp_std::{fmt::Debug, marker::PhantomData};
+#[cfg(feature = "std")]
+use sp_std::collections::btree_map::BTreeMap;
+use sp_std::{fmt::Debug, marker::PhantomData, vec::Vec};
+
+pub mod abi;
+mod assets_erc20;
+pub mod dispatch;
+mod erc20;
+mod filtered_dispatch;
+mod governance;
+mod identity;
+mod native_erc20;
+mod proxy;
+mod randomness;
+mod staking;
+mod wasm_bridge;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub use assets_erc20::{AssetsErc20, ASSET_PRECOMPILE_PREFIX};
+pub use filtered_dispatch::FilteredDispatch;
+pub use governance::Governance;
+pub use identity::OnChainIdentity;
+pub use native_erc20::NativeErc20;
+pub use proxy::ProxyMultisig;
+pub use randomness::Randomness;
+pub use staking::Staking;
+pub use wasm_bridge::{EvmCall, EvmExtension, WasmBridge};
 
 #[derive(Debug, Clone, Copy)]
 pub struct EdgewarePrecompiles<R>(PhantomData<R>);
 
-impl<R: frame_system::Config> EdgewarePrecompiles<R> {
-	/// Return all addresses that contain precompiles. This can be used to
-	/// populate dummy code under the precompile, and potentially in the future
-	/// to prevent using accounts that have precompiles at their addresses
-	/// explicitly using something like SignedExtra.
-	#[allow(dead_code)]
-	fn used_addresses() -> impl Iterator<Item = H160> {
-		sp_std::vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 1024, 1025, 1026, 1027, 1028, 1029]
-			.into_iter()
-			.map(|x| hash(x).into())
+/// The code stored at every precompile address: `PUSH1 0 PUSH1 0 REVERT`.
+/// Calls to the address run the precompile rather than this code, but with it
+/// the address has a nonzero `EXTCODESIZE`, so contracts that check a callee is
+/// a contract accept precompiles.
+pub const PRECOMPILE_CODE: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xfd];
+
+impl<R> EdgewarePrecompiles<R> {
+	/// Return all addresses that contain precompiles, other than the
+	/// `pallet_assets` ERC-20 range, which holds no code. Genesis and the
+	/// `PrecompileCode` migration store [`PRECOMPILE_CODE`] at each of them.
+	pub fn used_addresses() -> impl Iterator<Item = H160> {
+		sp_std::vec![
+			1, 2, 3, 4, 5, 6, 7, 8, 9, 1024, 1025, 1026, 1027, 1028, 1029, 2048, 2049, 2050, 2051, 2052, 2053, 2054
+		]
+		.into_iter()
+		.map(hash)
+	}
+
+	/// The EVM genesis accounts holding [`PRECOMPILE_CODE`] at
+	/// [`Self::used_addresses`].
+	#[cfg(feature = "std")]
+	pub fn genesis_accounts() -> BTreeMap<H160, GenesisAccount> {
+		Self::used_addresses()
+			.map(|address| {
+				let account = GenesisAccount {
+					nonce: U256::zero(),
+					balance: U256::zero(),
+					storage: BTreeMap::new(),
+					code: PRECOMPILE_CODE.to_vec(),
+				};
+				(address, account)
+			})
+			.collect()
+	}
+}
+
+impl<R> EdgewarePrecompiles<R>
+where
+	R: pallet_assets::Config,
+	R::AssetId: From<u32> + Into<u32>,
+{
+	/// Whether `address` is reserved for a precompile, including the whole
+	/// `pallet_assets` ERC-20 range.
+	pub fn is_precompile(address: H160) -> bool {
+		Self::used_addresses().any(|used| used == address) || AssetsErc20::<R>::asset(address).is_some()
 	}
 }
 
@@ -34,10 +104,36 @@ impl<R: frame_system::Config> EdgewarePrecompiles<R> {
 /// 0-1023: Ethereum Mainnet Precompiles
 /// 1024-2047 Precompiles that are not in Ethereum Mainnet but are neither
 /// Moonbeam specific
-impl<R: frame_system::Config + pallet_evm::Config> PrecompileSet for EdgewarePrecompiles<R>
+/// 2048-4095 Edgeware specific precompiles
+/// 0xFFFFFFFF followed by an asset id: `pallet_assets` ERC-20 precompiles
+impl<R> PrecompileSet for EdgewarePrecompiles<R>
 where
-	R::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + Decode,
-	<R::Call as Dispatchable>::Origin: From<Option<R::AccountId>>,
+	R: frame_system::Config
+		+ pallet_evm::Config
+		+ pallet_assets::Config
+		+ pallet_staking::Config
+		+ pallet_democracy::Config
+		+ pallet_elections_phragmen::Config
+		+ pallet_identity::Config
+		+ pallet_proxy::Config
+		+ pallet_multisig::Config
+		+ pallet_randomness_collective_flip::Config
+		+ pallet_contracts::Config
+		+ evm_allowances::Config
+		+ evm_dispatch::Config,
+	R::AssetId: From<u32> + Into<u32>,
+	R::AccountId: UncheckedFrom<R::Hash> + AsRef<[u8]>,
+	<R as frame_system::Config>::Call: Dispatchable<Origin = R::Origin, PostInfo = PostDispatchInfo>
+		+ GetDispatchInfo
+		+ GetCallMetadata
+		+ Decode
+		+ From<pallet_staking::Call<R>>
+		+ From<pallet_democracy::Call<R>>
+		+ From<pallet_elections_phragmen::Call<R>>
+		+ From<pallet_identity::Call<R>>
+		+ From<pallet_proxy::Call<R>>
+		+ From<pallet_multisig::Call<R>>,
+	R::Origin: From<Option<R::AccountId>>,
 {
 	fn execute(
 		address: H160,
@@ -58,13 +154,89 @@ where
 			a if a == hash(9) => Some(Blake2F::execute(input, target_gas, context)),
 			// Non-Edgeware specific nor Ethereum precompiles :
 			a if a == hash(1024) => Some(Sha3FIPS256::execute(input, target_gas, context)),
-			a if a == hash(1025) => Some(Dispatch::<R>::execute(input, target_gas, context)),
+			a if a == hash(1025) => Some(FilteredDispatch::<R>::execute(input, target_gas, context)),
 			a if a == hash(1026) => Some(ECRecoverPublicKey::execute(input, target_gas, context)),
 			a if a == hash(1027) => Some(Ed25519Verify::execute(input, target_gas, context)),
 			a if a == hash(1028) => Some(Curve25519Add::execute(input, target_gas, context)),
 			a if a == hash(1029) => Some(Curve25519ScalarMul::execute(input, target_gas, context)),
-			_ => None,
+			// Edgeware specific precompiles :
+			a if a == hash(2048) => Some(NativeErc20::<R>::execute(input, target_gas, context)),
+			a if a == hash(2049) => Some(Staking::<R>::execute(input, target_gas, context)),
+			a if a == hash(2050) => Some(Governance::<R>::execute(input, target_gas, context)),
+			a if a == hash(2051) => Some(OnChainIdentity::<R>::execute(input, target_gas, context)),
+			a if a == hash(2052) => Some(ProxyMultisig::<R>::execute(input, target_gas, context)),
+			a if a == hash(2053) => Some(Randomness::<R>::execute(input, target_gas, context)),
+			a if a == hash(2054) => Some(WasmBridge::<R>::execute(input, target_gas, context)),
+			_ => AssetsErc20::<R>::execute(address, input, target_gas, context),
+		}
+	}
+}
+
+/// Frontier's stack runner, refusing transactions sent from a precompile
+/// address. Nobody holds the keys to those addresses, but a source is only
+/// checked against a signature or origin, and an account there would be able
+/// to act as the precompile.
+pub struct PrecompileSourceRunner<R>(PhantomData<R>);
+
+impl<R> PrecompileSourceRunner<R>
+where
+	R: pallet_assets::Config,
+	R::AssetId: From<u32> + Into<u32>,
+{
+	fn ensure_not_precompile(source: H160) -> Result<(), DispatchError> {
+		if EdgewarePrecompiles::<R>::is_precompile(source) {
+			return Err(DispatchError::Other("transaction sent from a precompile address"));
 		}
+		Ok(())
+	}
+}
+
+impl<R> Runner<R> for PrecompileSourceRunner<R>
+where
+	R: pallet_evm::Config + pallet_assets::Config,
+	R::AssetId: From<u32> + Into<u32>,
+{
+	type Error = DispatchError;
+
+	fn call(
+		source: H160,
+		target: H160,
+		input: Vec<u8>,
+		value: U256,
+		gas_limit: u64,
+		gas_price: Option<U256>,
+		nonce: Option<U256>,
+		config: &evm::Config,
+	) -> Result<CallInfo, Self::Error> {
+		Self::ensure_not_precompile(source)?;
+		StackRunner::<R>::call(source, target, input, value, gas_limit, gas_price, nonce, config).map_err(Into::into)
+	}
+
+	fn create(
+		source: H160,
+		init: Vec<u8>,
+		value: U256,
+		gas_limit: u64,
+		gas_price: Option<U256>,
+		nonce: Option<U256>,
+		config: &evm::Config,
+	) -> Result<CreateInfo, Self::Error> {
+		Self::ensure_not_precompile(source)?;
+		StackRunner::<R>::create(source, init, value, gas_limit, gas_price, nonce, config).map_err(Into::into)
+	}
+
+	fn create2(
+		source: H160,
+		init: Vec<u8>,
+		salt: H256,
+		value: U256,
+		gas_limit: u64,
+		gas_price: Option<U256>,
+		nonce: Option<U256>,
+		config: &evm::Config,
+	) -> Result<CreateInfo, Self::Error> {
+		Self::ensure_not_precompile(source)?;
+		StackRunner::<R>::create2(source, init, salt, value, gas_limit, gas_price, nonce, config).map_err(Into::into)
 	}
 }
 
@@ -0,0 +1,396 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reading Solidity ABI call data and writing ABI return data.
+//!
+//! A precompile splits its input with [`EvmDataReader::new_with_selector`],
+//! matches on the selector, then reads each argument in order with
+//! [`EvmDataReader::read`]. Return values are built the same way with
+//! [`EvmDataWriter`]. Both handle the head/tail layout of dynamic types, so
+//! `bytes`, `string` and arrays (including arrays of dynamic types) can be
+//! read and written like any other value. Structs implement [`EvmData`] by
+//! reading and writing their members in order, through
+//! [`EvmDataReader::read_struct`] and [`EvmDataWriter::write_struct`] if any
+//! member is dynamic.
+
+use codec::{Decode, Encode};
+use evm::ExitError;
+use sp_core::{H160, H256, U256};
+use sp_runtime::traits::UniqueSaturatedInto;
+use sp_std::{convert::TryFrom, vec, vec::Vec};
+
+/// The size of an ABI word.
+pub const WORD: usize = 32;
+
+/// `Error(string)`, the selector of the data a Solidity `revert(reason)`
+/// returns.
+pub const SELECTOR_ERROR: u32 = 0x08c379a0;
+
+/// The result of reading input or running a precompile.
+pub type EvmResult<T = ()> = Result<T, ExitError>;
+
+/// An error carrying `message` as its reason.
+pub fn error(message: &'static str) -> ExitError {
+	ExitError::Other(message.into())
+}
+
+/// Encodes `message` the way Solidity encodes a revert reason, as a call to
+/// `Error(string)`.
+pub fn revert_reason(message: &[u8]) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(SELECTOR_ERROR)
+		.write(Bytes(message.to_vec()))
+		.build()
+}
+
+/// A value of dynamic length: `bytes` or `string`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bytes(pub Vec<u8>);
+
+impl From<&[u8]> for Bytes {
+	fn from(bytes: &[u8]) -> Self {
+		Bytes(bytes.to_vec())
+	}
+}
+
+/// Reads ABI-encoded values in order.
+#[derive(Clone, Copy, Debug)]
+pub struct EvmDataReader<'a> {
+	input: &'a [u8],
+	cursor: usize,
+}
+
+impl<'a> EvmDataReader<'a> {
+	/// Reads the values in `input`, which has no selector.
+	pub fn new(input: &'a [u8]) -> Self {
+		EvmDataReader { input, cursor: 0 }
+	}
+
+	/// Splits call data into its function selector and a reader over its
+	/// arguments.
+	pub fn new_with_selector(input: &'a [u8]) -> EvmResult<(u32, Self)> {
+		if input.len() < 4 {
+			return Err(error("input is too short for a selector"));
+		}
+		let mut selector = [0u8; 4];
+		selector.copy_from_slice(&input[..4]);
+		Ok((u32::from_be_bytes(selector), Self::new(&input[4..])))
+	}
+
+	/// Reads the next value.
+	pub fn read<T: EvmData>(&mut self) -> EvmResult<T> {
+		T::read(self)
+	}
+
+	/// Reads the next value as a `bytes32` holding a SCALE-encoded value, such
+	/// as an account id or a hash.
+	pub fn read_decoded<T: Decode>(&mut self) -> EvmResult<T> {
+		T::decode(&mut self.read_word()?).map_err(|_| error("argument is not a valid bytes32 value"))
+	}
+
+	/// Reads the offset of a struct with dynamic members, returning a reader
+	/// over its members. Structs with only static members are read in place.
+	pub fn read_struct(&mut self) -> EvmResult<Self> {
+		self.read_pointer()
+	}
+
+	/// The input after the cursor.
+	fn rest(&self) -> &'a [u8] {
+		&self.input[self.cursor..]
+	}
+
+	fn read_word(&mut self) -> EvmResult<&'a [u8]> {
+		let word = self
+			.cursor
+			.checked_add(WORD)
+			.and_then(|end| self.input.get(self.cursor..end))
+			.ok_or_else(|| error("input is too short"))?;
+		self.cursor += WORD;
+		Ok(word)
+	}
+
+	/// Reads an offset and returns a reader over the dynamic data it points
+	/// at, relative to the start of this reader's input.
+	fn read_pointer(&mut self) -> EvmResult<Self> {
+		let offset: u32 = self.read()?;
+		let input = self
+			.input
+			.get(offset as usize..)
+			.ok_or_else(|| error("offset is out of bounds"))?;
+		Ok(Self::new(input))
+	}
+}
+
+/// Writes ABI-encoded values in order.
+#[derive(Clone, Debug, Default)]
+pub struct EvmDataWriter {
+	selector: Option<u32>,
+	head: Vec<u8>,
+	/// The dynamic data, each with the position of the offset word pointing at
+	/// it in `head`.
+	tails: Vec<(usize, Vec<u8>)>,
+}
+
+impl EvmDataWriter {
+	/// Writes return data.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Writes call data for the function with `selector`.
+	pub fn new_with_selector(selector: u32) -> Self {
+		EvmDataWriter {
+			selector: Some(selector),
+			..Default::default()
+		}
+	}
+
+	/// Writes `value`.
+	pub fn write<T: EvmData>(mut self, value: T) -> Self {
+		T::write(&mut self, value);
+		self
+	}
+
+	/// Writes `value` SCALE-encoded into a `bytes32`, the inverse of
+	/// [`EvmDataReader::read_decoded`].
+	pub fn write_encoded<T: Encode>(mut self, value: &T) -> Self {
+		let mut word = value.encode();
+		word.resize(WORD, 0);
+		self.head.extend(word);
+		self
+	}
+
+	/// Returns the encoded data.
+	pub fn build(self) -> Vec<u8> {
+		let mut out = self.head;
+		for (position, tail) in self.tails {
+			let offset = U256::from(out.len());
+			offset.to_big_endian(&mut out[position..position + WORD]);
+			out.extend(tail);
+		}
+		match self.selector {
+			Some(selector) => {
+				let mut data = selector.to_be_bytes().to_vec();
+				data.extend(out);
+				data
+			}
+			None => out,
+		}
+	}
+
+	/// Writes a struct with dynamic members, whose members are written to
+	/// `members`. Structs with only static members are written in place.
+	pub fn write_struct(&mut self, members: EvmDataWriter) {
+		self.write_tail(members.build())
+	}
+
+	fn write_word(&mut self, word: U256) {
+		let mut out = [0u8; WORD];
+		word.to_big_endian(&mut out);
+		self.head.extend_from_slice(&out);
+	}
+
+	/// Writes an offset to `tail`, which is appended after the head.
+	fn write_tail(&mut self, tail: Vec<u8>) {
+		self.tails.push((self.head.len(), tail));
+		self.head.extend_from_slice(&[0u8; WORD]);
+	}
+}
+
+/// A Solidity type that can be read from and written as ABI data.
+pub trait EvmData: Sized {
+	/// Reads a value at the cursor of `reader`.
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self>;
+
+	/// Writes `value` to `writer`.
+	fn write(writer: &mut EvmDataWriter, value: Self);
+}
+
+/// `uint256`
+impl EvmData for U256 {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		Ok(U256::from_big_endian(reader.read_word()?))
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		writer.write_word(value)
+	}
+}
+
+/// `uint64`, rejecting words that do not fit.
+impl EvmData for u64 {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let value: U256 = reader.read()?;
+		if value > U256::from(u64::max_value()) {
+			return Err(error("argument is not a uint64"));
+		}
+		Ok(value.low_u64())
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		writer.write_word(value.into())
+	}
+}
+
+/// `uint32`, rejecting words that do not fit.
+impl EvmData for u32 {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let value: U256 = reader.read()?;
+		if value > U256::from(u32::max_value()) {
+			return Err(error("argument is not a uint32"));
+		}
+		Ok(value.low_u32())
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		writer.write_word(value.into())
+	}
+}
+
+/// `uint16`, rejecting words that do not fit.
+impl EvmData for u16 {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let value: U256 = reader.read()?;
+		if value > U256::from(u16::max_value()) {
+			return Err(error("argument is not a uint16"));
+		}
+		Ok(value.low_u32() as u16)
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		writer.write_word(value.into())
+	}
+}
+
+/// `uint8`, rejecting words that do not fit.
+impl EvmData for u8 {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let value: U256 = reader.read()?;
+		if value > U256::from(u8::max_value()) {
+			return Err(error("argument is not a uint8"));
+		}
+		Ok(value.low_u32() as u8)
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		writer.write_word(value.into())
+	}
+}
+
+/// `bool`, rejecting words other than 0 and 1.
+impl EvmData for bool {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let value: U256 = reader.read()?;
+		if value > U256::one() {
+			return Err(error("argument is not a bool"));
+		}
+		Ok(!value.is_zero())
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		writer.write_word((value as u8).into())
+	}
+}
+
+/// `address`, rejecting words with dirty upper bytes.
+impl EvmData for H160 {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let word = reader.read_word()?;
+		if word[..12].iter().any(|b| *b != 0) {
+			return Err(error("argument is not an address"));
+		}
+		Ok(H160::from_slice(&word[12..]))
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		H256::write(writer, value.into())
+	}
+}
+
+/// `bytes32`
+impl EvmData for H256 {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		Ok(H256::from_slice(reader.read_word()?))
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		writer.head.extend_from_slice(value.as_bytes());
+	}
+}
+
+/// `bytes` or `string`
+impl EvmData for Bytes {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let mut inner = reader.read_pointer()?;
+		let len: u32 = inner.read()?;
+		let bytes = inner
+			.rest()
+			.get(..len as usize)
+			.ok_or_else(|| error("bytes are longer than the input"))?;
+		Ok(Bytes(bytes.to_vec()))
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		let mut tail = EvmDataWriter::new().write(U256::from(value.0.len())).build();
+		let padding = (WORD - value.0.len() % WORD) % WORD;
+		tail.extend(value.0);
+		tail.extend(vec![0u8; padding]);
+		writer.write_tail(tail);
+	}
+}
+
+/// `T[]`
+impl<T: EvmData> EvmData for Vec<T> {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let mut inner = reader.read_pointer()?;
+		let len: u32 = inner.read()?;
+		// Every item takes at least a word, so this bounds what is allocated by
+		// the size of the input.
+		if (len as usize).saturating_mul(WORD) > inner.rest().len() {
+			return Err(error("array is longer than the input"));
+		}
+		let mut items = EvmDataReader::new(inner.rest());
+		(0..len).map(|_| items.read()).collect()
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		let mut tail = EvmDataWriter::new().write(U256::from(value.len())).build();
+		tail.extend(
+			value
+				.into_iter()
+				.fold(EvmDataWriter::new(), EvmDataWriter::write)
+				.build(),
+		);
+		writer.write_tail(tail);
+	}
+}
+
+/// Converts a `uint256` amount to a balance, failing if it does not fit.
+pub fn to_balance<B: TryFrom<u128>>(amount: U256) -> EvmResult<B> {
+	if amount > U256::from(u128::max_value()) {
+		return Err(error("amount is too large"));
+	}
+	B::try_from(amount.low_u128()).map_err(|_| error("amount is too large"))
+}
+
+/// Converts a balance to a `uint256` amount.
+pub fn from_balance<B: UniqueSaturatedInto<u128>>(balance: B) -> U256 {
+	U256::from(balance.unique_saturated_into())
+}
+
+/// Converts a block number to a `uint256`.
+pub fn from_block_number<N: UniqueSaturatedInto<u128>>(number: N) -> U256 {
+	U256::from(number.unique_saturated_into())
+}
@@ -0,0 +1,141 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The ERC-20 interface to `pallet_assets`. Every asset gets its own address:
+//! `ASSET_PRECOMPILE_PREFIX`, twelve zero bytes, then the big-endian asset id.
+//!
+//! Asset addresses hold no code: the precompile answers at the address of any
+//! asset, whether or not `pallet_evm` has code stored there. Contracts calling
+//! an asset must therefore not check its `EXTCODESIZE`, which Solidity 0.8.10
+//! and later skip for calls that return data.
+
+use super::{
+	abi::{self, EvmResult},
+	erc20::{self, Erc20Token},
+};
+use evm::{executor::PrecompileOutput, Context};
+use frame_support::{
+	traits::{
+		tokens::fungibles::{metadata::Inspect as InspectMetadata, Inspect, Transfer},
+		Get,
+	},
+	weights::Weight,
+};
+use pallet_evm::{AddressMapping, PrecompileSet};
+use sp_core::{H160, U256};
+use sp_runtime::traits::Zero;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// The first bytes of every asset precompile address.
+pub const ASSET_PRECOMPILE_PREFIX: [u8; 4] = [0xff; 4];
+
+/// The ERC-20 precompiles for `pallet_assets`, one per asset.
+pub struct AssetsErc20<R: pallet_assets::Config> {
+	asset: R::AssetId,
+	_marker: PhantomData<R>,
+}
+
+impl<R> AssetsErc20<R>
+where
+	R: pallet_assets::Config,
+	R::AssetId: From<u32> + Into<u32>,
+{
+	/// The precompile address of `asset`.
+	pub fn address(asset: R::AssetId) -> H160 {
+		let mut address = [0u8; 20];
+		address[..4].copy_from_slice(&ASSET_PRECOMPILE_PREFIX);
+		address[16..].copy_from_slice(&asset.into().to_be_bytes());
+		H160(address)
+	}
+
+	/// The asset whose precompile lives at `address`, if `address` is in the
+	/// asset range.
+	pub fn asset(address: H160) -> Option<R::AssetId> {
+		let bytes = address.as_bytes();
+		if bytes[..4] != ASSET_PRECOMPILE_PREFIX || bytes[4..16].iter().any(|b| *b != 0) {
+			return None;
+		}
+		let mut id = [0u8; 4];
+		id.copy_from_slice(&bytes[16..]);
+		Some(u32::from_be_bytes(id).into())
+	}
+}
+
+impl<R: pallet_assets::Config + pallet_evm::Config> Erc20Token for AssetsErc20<R> {
+	fn name(&self) -> Vec<u8> {
+		pallet_assets::Pallet::<R>::name(&self.asset)
+	}
+
+	fn symbol(&self) -> Vec<u8> {
+		pallet_assets::Pallet::<R>::symbol(&self.asset)
+	}
+
+	fn decimals(&self) -> u8 {
+		pallet_assets::Pallet::<R>::decimals(&self.asset)
+	}
+
+	fn total_supply(&self) -> U256 {
+		abi::from_balance(pallet_assets::Pallet::<R>::total_issuance(self.asset))
+	}
+
+	fn balance_of(&self, who: H160) -> U256 {
+		abi::from_balance(pallet_assets::Pallet::<R>::balance(
+			self.asset,
+			&R::AddressMapping::into_account_id(who),
+		))
+	}
+
+	fn transfer(&self, from: H160, to: H160, amount: U256) -> EvmResult {
+		<pallet_assets::Pallet<R> as Transfer<R::AccountId>>::transfer(
+			self.asset,
+			&R::AddressMapping::into_account_id(from),
+			&R::AddressMapping::into_account_id(to),
+			abi::to_balance(amount)?,
+			false,
+		)
+		.map(|_| ())
+		.map_err(|_| abi::error("transfer failed"))
+	}
+
+	fn transfer_weight(&self) -> Weight {
+		R::DbWeight::get().reads_writes(3, 3)
+	}
+}
+
+impl<R> PrecompileSet for AssetsErc20<R>
+where
+	R: pallet_assets::Config + pallet_evm::Config + evm_allowances::Config,
+	R::AssetId: From<u32> + Into<u32>,
+{
+	fn execute(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> Option<EvmResult<PrecompileOutput>> {
+		let asset = Self::asset(address)?;
+		// Assets cannot be created with a zero minimum balance, so a zero one
+		// means there is no asset.
+		if pallet_assets::Pallet::<R>::minimum_balance(asset).is_zero() {
+			return Some(Err(abi::error("asset does not exist")));
+		}
+		let token = AssetsErc20::<R> {
+			asset,
+			_marker: PhantomData,
+		};
+		Some(erc20::execute::<R, _>(&token, address, input, target_gas, context))
+	}
+}
@@ -0,0 +1,158 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Gas accounting, and dispatching runtime calls on behalf of EVM callers.
+
+use super::abi::{error, EvmResult};
+use evm::{backend::Log, executor::PrecompileOutput, Context, ExitError, ExitSucceed};
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	traits::{CallMetadata, Get, GetCallMetadata},
+	weights::Weight,
+};
+use pallet_evm::{AddressMapping, GasWeightMapping};
+use sp_core::H160;
+use sp_std::{vec, vec::Vec};
+
+/// The deepest a runtime call passed in from the EVM may nest other calls, the
+/// same limit extrinsics are decoded with.
+pub const MAX_CALL_DEPTH: u32 = 256;
+
+/// Gas for a `LOG` with no topics or data, as the EVM would charge it.
+const LOG_COST: u64 = 375;
+/// Gas per `LOG` topic.
+const LOG_TOPIC_COST: u64 = 375;
+/// Gas per byte of `LOG` data.
+const LOG_DATA_COST: u64 = 8;
+
+/// Tracks the gas a precompile uses against the gas it was given.
+#[derive(Clone, Copy, Debug)]
+pub struct Gasometer {
+	used: u64,
+	target_gas: Option<u64>,
+}
+
+impl Gasometer {
+	/// A gasometer for a call given `target_gas`, or unlimited gas if `None`.
+	pub fn new(target_gas: Option<u64>) -> Self {
+		Gasometer { used: 0, target_gas }
+	}
+
+	/// The gas used so far.
+	pub fn used(&self) -> u64 {
+		self.used
+	}
+
+	/// The gas left, or `None` if it is unlimited.
+	pub fn remaining(&self) -> Option<u64> {
+		self.target_gas.map(|target_gas| target_gas.saturating_sub(self.used))
+	}
+
+	/// Uses `cost` gas, failing if that is more than is left.
+	pub fn record_cost(&mut self, cost: u64) -> EvmResult {
+		let used = self.used.saturating_add(cost);
+		if self.target_gas.map_or(false, |target_gas| used > target_gas) {
+			return Err(ExitError::OutOfGas);
+		}
+		self.used = used;
+		Ok(())
+	}
+
+	/// Uses the gas `weight` maps to.
+	pub fn record_weight<R: pallet_evm::Config>(&mut self, weight: Weight) -> EvmResult {
+		self.record_cost(R::GasWeightMapping::weight_to_gas(weight))
+	}
+
+	/// Uses the gas for reading `reads` storage items.
+	pub fn record_reads<R: pallet_evm::Config>(&mut self, reads: u64) -> EvmResult {
+		self.record_weight::<R>(R::DbWeight::get().reads(reads))
+	}
+
+	/// Uses the gas the EVM would charge for emitting `logs`.
+	pub fn record_log_costs(&mut self, logs: &[Log]) -> EvmResult {
+		let cost = logs.iter().fold(0u64, |cost, log| {
+			cost.saturating_add(LOG_COST)
+				.saturating_add(LOG_TOPIC_COST.saturating_mul(log.topics.len() as u64))
+				.saturating_add(LOG_DATA_COST.saturating_mul(log.data.len() as u64))
+		});
+		self.record_cost(cost)
+	}
+
+	/// Fails unless the gas `weight` maps to is no more than is left, without
+	/// using it.
+	pub fn ensure_weight<R: pallet_evm::Config>(&self, weight: Weight) -> EvmResult {
+		let mut gasometer = *self;
+		gasometer.record_weight::<R>(weight)
+	}
+}
+
+/// Fails unless the precompile at `address` was called directly. Under
+/// `DELEGATECALL` or `CALLCODE` the context is the calling contract's, and the
+/// precompile would act as that contract's caller.
+pub fn ensure_direct_call(context: &Context, address: H160) -> EvmResult {
+	if context.address != address {
+		return Err(error("cannot be called with DELEGATECALL or CALLCODE"));
+	}
+	Ok(())
+}
+
+/// The error for a call that `evm_dispatch`'s call filter rejected, naming it
+/// as `Pallet.call`.
+pub fn not_allowed(call: &impl GetCallMetadata) -> ExitError {
+	let CallMetadata {
+		pallet_name,
+		function_name,
+	} = call.get_call_metadata();
+	let message = ["call ", pallet_name, ".", function_name, " is not allowed"].concat();
+	ExitError::Other(message.into())
+}
+
+/// Dispatches `call` with the account `caller` maps to as the signed origin.
+/// The gas for the call's declared weight must be left, and only the gas for
+/// its actual weight is used.
+pub fn dispatch_as<R>(gasometer: &mut Gasometer, caller: H160, call: impl Into<R::Call>) -> EvmResult
+where
+	R: pallet_evm::Config,
+	R::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	<R::Call as Dispatchable>::Origin: From<Option<R::AccountId>>,
+{
+	let call = call.into();
+	let info = call.get_dispatch_info();
+	gasometer.ensure_weight::<R>(info.weight)?;
+
+	let origin = Some(R::AddressMapping::into_account_id(caller)).into();
+	let post_info = call
+		.dispatch(origin)
+		.map_err(|e| error(Into::<&'static str>::into(e.error)))?;
+	gasometer.record_weight::<R>(post_info.actual_weight.unwrap_or(info.weight))
+}
+
+/// The output of a precompile that used `gasometer`'s gas and returned
+/// `output`.
+pub fn succeed(gasometer: &Gasometer, output: Vec<u8>) -> PrecompileOutput {
+	succeed_with_logs(gasometer, output, vec![])
+}
+
+/// The output of a precompile that used `gasometer`'s gas, returned `output`
+/// and emitted `logs`. The logs must already be paid for.
+pub fn succeed_with_logs(gasometer: &Gasometer, output: Vec<u8>, logs: Vec<Log>) -> PrecompileOutput {
+	PrecompileOutput {
+		exit_status: ExitSucceed::Returned,
+		cost: gasometer.used(),
+		output,
+		logs,
+	}
+}
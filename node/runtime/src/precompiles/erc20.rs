@@ -0,0 +1,185 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The ERC-20 interface shared by the token precompiles. Each token provides
+//! balances and transfers, while allowances are kept in `evm_allowances`.
+
+use super::{
+	abi::{error, Bytes, EvmDataReader, EvmDataWriter, EvmResult},
+	dispatch::{self, Gasometer},
+};
+use evm::{backend::Log, executor::PrecompileOutput, Context};
+use frame_support::{traits::Get, weights::Weight};
+use sp_core::{H160, H256, U256};
+use sp_io::hashing::keccak_256;
+use sp_std::{vec, vec::Vec};
+
+/// `name()`
+pub const SELECTOR_NAME: u32 = 0x06fdde03;
+/// `symbol()`
+pub const SELECTOR_SYMBOL: u32 = 0x95d89b41;
+/// `decimals()`
+pub const SELECTOR_DECIMALS: u32 = 0x313ce567;
+/// `totalSupply()`
+pub const SELECTOR_TOTAL_SUPPLY: u32 = 0x18160ddd;
+/// `balanceOf(address)`
+pub const SELECTOR_BALANCE_OF: u32 = 0x70a08231;
+/// `allowance(address,address)`
+pub const SELECTOR_ALLOWANCE: u32 = 0xdd62ed3e;
+/// `transfer(address,uint256)`
+pub const SELECTOR_TRANSFER: u32 = 0xa9059cbb;
+/// `approve(address,uint256)`
+pub const SELECTOR_APPROVE: u32 = 0x095ea7b3;
+/// `transferFrom(address,address,uint256)`
+pub const SELECTOR_TRANSFER_FROM: u32 = 0x23b872dd;
+
+/// A fungible token exposed through the ERC-20 interface.
+pub trait Erc20Token {
+	/// The token name.
+	fn name(&self) -> Vec<u8>;
+
+	/// The token symbol.
+	fn symbol(&self) -> Vec<u8>;
+
+	/// The number of decimals balances are given in.
+	fn decimals(&self) -> u8;
+
+	/// The amount in existence.
+	fn total_supply(&self) -> U256;
+
+	/// The balance of `who`.
+	fn balance_of(&self, who: H160) -> U256;
+
+	/// Moves `amount` from `from` to `to`.
+	fn transfer(&self, from: H160, to: H160, amount: U256) -> EvmResult;
+
+	/// The weight of a `transfer`.
+	fn transfer_weight(&self) -> Weight;
+}
+
+/// Executes an ERC-20 call on `token`, whose precompile lives at `address`.
+pub fn execute<R, T>(
+	token: &T,
+	address: H160,
+	input: &[u8],
+	target_gas: Option<u64>,
+	context: &Context,
+) -> EvmResult<PrecompileOutput>
+where
+	R: pallet_evm::Config + evm_allowances::Config,
+	T: Erc20Token,
+{
+	dispatch::ensure_direct_call(context, address)?;
+	let mut gasometer = Gasometer::new(target_gas);
+	let (selector, mut input) = EvmDataReader::new_with_selector(input)?;
+	let (output, logs) = match selector {
+		SELECTOR_NAME => {
+			gasometer.record_reads::<R>(1)?;
+			(EvmDataWriter::new().write(Bytes(token.name())), vec![])
+		}
+		SELECTOR_SYMBOL => {
+			gasometer.record_reads::<R>(1)?;
+			(EvmDataWriter::new().write(Bytes(token.symbol())), vec![])
+		}
+		SELECTOR_DECIMALS => {
+			gasometer.record_reads::<R>(1)?;
+			(EvmDataWriter::new().write(token.decimals()), vec![])
+		}
+		SELECTOR_TOTAL_SUPPLY => {
+			gasometer.record_reads::<R>(1)?;
+			(EvmDataWriter::new().write(token.total_supply()), vec![])
+		}
+		SELECTOR_BALANCE_OF => {
+			let who = input.read()?;
+			gasometer.record_reads::<R>(1)?;
+			(EvmDataWriter::new().write(token.balance_of(who)), vec![])
+		}
+		SELECTOR_ALLOWANCE => {
+			let owner = input.read()?;
+			let spender = input.read()?;
+			gasometer.record_reads::<R>(1)?;
+			let allowance = evm_allowances::Pallet::<R>::allowance(address, owner, spender);
+			(EvmDataWriter::new().write(allowance), vec![])
+		}
+		SELECTOR_TRANSFER => {
+			let to = input.read()?;
+			let amount = input.read()?;
+			let log = transfer_log(address, context.caller, to, amount);
+			gasometer.record_weight::<R>(token.transfer_weight())?;
+			gasometer.record_log_costs(&[log.clone()])?;
+			token.transfer(context.caller, to, amount)?;
+			(EvmDataWriter::new().write(true), vec![log])
+		}
+		SELECTOR_APPROVE => {
+			let spender = input.read()?;
+			let amount = input.read()?;
+			let log = approval_log(address, context.caller, spender, amount);
+			gasometer.record_weight::<R>(R::DbWeight::get().writes(1))?;
+			gasometer.record_log_costs(&[log.clone()])?;
+			evm_allowances::Pallet::<R>::approve(address, context.caller, spender, amount);
+			(EvmDataWriter::new().write(true), vec![log])
+		}
+		SELECTOR_TRANSFER_FROM => {
+			let from = input.read()?;
+			let to = input.read()?;
+			let amount = input.read()?;
+			let log = transfer_log(address, from, to, amount);
+			gasometer.record_weight::<R>(token.transfer_weight())?;
+			gasometer.record_weight::<R>(R::DbWeight::get().reads_writes(1, 1))?;
+			gasometer.record_log_costs(&[log.clone()])?;
+			let spends_allowance = from != context.caller;
+			// Check the allowance first so that a failed transfer leaves it untouched.
+			if spends_allowance && evm_allowances::Pallet::<R>::allowance(address, from, context.caller) < amount {
+				return Err(error("insufficient allowance"));
+			}
+			token.transfer(from, to, amount)?;
+			if spends_allowance {
+				evm_allowances::Pallet::<R>::spend(address, from, context.caller, amount)
+					.map_err(|_| error("insufficient allowance"))?;
+			}
+			(EvmDataWriter::new().write(true), vec![log])
+		}
+		_ => return Err(error("unknown ERC-20 selector")),
+	};
+
+	Ok(dispatch::succeed_with_logs(&gasometer, output.build(), logs))
+}
+
+/// `Transfer(address indexed from, address indexed to, uint256 value)`
+fn transfer_log(token: H160, from: H160, to: H160, amount: U256) -> Log {
+	Log {
+		address: token,
+		topics: vec![
+			H256(keccak_256(b"Transfer(address,address,uint256)")),
+			from.into(),
+			to.into(),
+		],
+		data: EvmDataWriter::new().write(amount).build(),
+	}
+}
+
+/// `Approval(address indexed owner, address indexed spender, uint256 value)`
+fn approval_log(token: H160, owner: H160, spender: H160, amount: U256) -> Log {
+	Log {
+		address: token,
+		topics: vec![
+			H256(keccak_256(b"Approval(address,address,uint256)")),
+			owner.into(),
+			spender.into(),
+		],
+		data: EvmDataWriter::new().write(amount).build(),
+	}
+}
@@ -0,0 +1,74 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Dispatches a SCALE-encoded runtime call with the caller's mapped account as
+//! the signed origin, like Frontier's `Dispatch` precompile, but only if
+//! `evm_dispatch`'s call filter allows it.
+//!
+//! A rejected call is not dispatched and the precompile call fails with an
+//! error naming the call as `Pallet.call`. Logs of a failed call are dropped,
+//! so the runtime keeps `evm_dispatch::Event::CallRejected` as the lasting
+//! record of the attempt, naming the caller and the call.
+
+use super::{
+	abi::{error, EvmResult},
+	dispatch::{self, Gasometer, MAX_CALL_DEPTH},
+	hash,
+};
+use codec::{Decode, DecodeLimit};
+use evm::{executor::PrecompileOutput, Context};
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	traits::{Get, GetCallMetadata, OriginTrait},
+};
+use pallet_evm::{AddressMapping, Precompile};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// The Dispatch precompile, restricted by `evm_dispatch`'s call filter.
+pub struct FilteredDispatch<R>(PhantomData<R>);
+
+impl<R> Precompile for FilteredDispatch<R>
+where
+	R: evm_dispatch::Config + pallet_evm::Config,
+	<R as frame_system::Config>::Call:
+		Dispatchable<Origin = R::Origin, PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata + Decode,
+{
+	fn execute(input: &[u8], target_gas: Option<u64>, context: &Context) -> EvmResult<PrecompileOutput> {
+		dispatch::ensure_direct_call(context, hash(1025))?;
+		let mut gasometer = Gasometer::new(target_gas);
+		let call = <R as frame_system::Config>::Call::decode_with_depth_limit(MAX_CALL_DEPTH, &mut &*input)
+			.map_err(|_| error("call could not be decoded"))?;
+		let who = R::AddressMapping::into_account_id(context.caller);
+
+		if let Some(rejected) = evm_dispatch::Pallet::<R>::rejected_call(&call) {
+			// The event is a storage write.
+			gasometer.record_weight::<R>(R::DbWeight::get().writes(1))?;
+			evm_dispatch::Pallet::<R>::note_rejected(who, rejected);
+			return Err(dispatch::not_allowed(rejected));
+		}
+
+		let info = call.get_dispatch_info();
+		gasometer.ensure_weight::<R>(info.weight)?;
+		let mut origin: R::Origin = frame_system::RawOrigin::Signed(who).into();
+		// Calls dispatched from within this one are held to the same filter.
+		origin.add_filter(|call| evm_dispatch::Pallet::<R>::rejected_call(call).is_none());
+		let post_info = call
+			.dispatch(origin)
+			.map_err(|e| error(Into::<&'static str>::into(e.error)))?;
+		gasometer.record_weight::<R>(post_info.actual_weight.unwrap_or(info.weight))?;
+		Ok(dispatch::succeed(&gasometer, Vec::new()))
+	}
+}
@@ -0,0 +1,239 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Lets contracts take part in on-chain governance through `pallet_democracy`
+//! and `pallet_elections_phragmen`. Calls are dispatched with the caller's
+//! mapped account as the origin, and accounts are passed as `bytes32`.
+//!
+//! ```solidity
+//! interface Governance {
+//!     function propose(bytes32 proposalHash, uint256 value) external;
+//!     function second(uint32 propIndex, uint32 secondsUpperBound) external;
+//!     // conviction: 0 = None, 1 = Locked1x, ..., 6 = Locked6x
+//!     function standardVote(uint32 refIndex, bool aye, uint256 value, uint8 conviction) external;
+//!     function removeVote(uint32 refIndex) external;
+//!     function delegate(bytes32 to, uint8 conviction, uint256 value) external;
+//!     function undelegate() external;
+//!     function voteCandidates(bytes32[] calldata candidates, uint256 value) external;
+//!     function removeVoter() external;
+//!     function publicPropCount() external view returns (uint32);
+//!     // Returns (propIndex, proposalHash, proposer) of the `position`th public proposal
+//!     function publicProp(uint32 position) external view returns (uint32, bytes32, bytes32);
+//!     // Returns (deposit, seconds)
+//!     function depositOf(uint32 propIndex) external view returns (uint256, uint32);
+//!     function referendumCount() external view returns (uint32);
+//!     // status: 0 = Unknown, 1 = Ongoing, 2 = Approved, 3 = Rejected
+//!     // threshold: 0 = SuperMajorityApprove, 1 = SuperMajorityAgainst, 2 = SimpleMajority
+//!     // Returns (status, end, proposalHash, threshold, delay)
+//!     function referendumInfo(uint32 refIndex) external view returns (uint8, uint32, bytes32, uint8, uint32);
+//!     // Returns (ayes, nays, turnout) of an ongoing referendum
+//!     function referendumTally(uint32 refIndex) external view returns (uint256, uint256, uint256);
+//! }
+//! ```
+
+use super::{
+	abi::{self, error, EvmDataReader, EvmDataWriter, EvmResult},
+	dispatch::{self, Gasometer},
+	hash,
+};
+use evm::{executor::PrecompileOutput, Context};
+use frame_support::dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo};
+use pallet_democracy::{AccountVote, Conviction, ReferendumInfo, Vote, VoteThreshold};
+use pallet_evm::Precompile;
+use sp_core::{H256, U256};
+use sp_std::{convert::TryFrom, marker::PhantomData, vec::Vec};
+
+/// `propose(bytes32,uint256)`
+pub const SELECTOR_PROPOSE: u32 = 0x7824e7d1;
+/// `second(uint32,uint32)`
+pub const SELECTOR_SECOND: u32 = 0xa91e19fe;
+/// `standardVote(uint32,bool,uint256,uint8)`
+pub const SELECTOR_STANDARD_VOTE: u32 = 0x3da493f0;
+/// `removeVote(uint32)`
+pub const SELECTOR_REMOVE_VOTE: u32 = 0x79cae220;
+/// `delegate(bytes32,uint8,uint256)`
+pub const SELECTOR_DELEGATE: u32 = 0x93db12f3;
+/// `undelegate()`
+pub const SELECTOR_UNDELEGATE: u32 = 0x92ab89bb;
+/// `voteCandidates(bytes32[],uint256)`
+pub const SELECTOR_VOTE_CANDIDATES: u32 = 0x045fc7ca;
+/// `removeVoter()`
+pub const SELECTOR_REMOVE_VOTER: u32 = 0x878479d9;
+/// `publicPropCount()`
+pub const SELECTOR_PUBLIC_PROP_COUNT: u32 = 0x31305462;
+/// `publicProp(uint32)`
+pub const SELECTOR_PUBLIC_PROP: u32 = 0xc6cc88b4;
+/// `depositOf(uint32)`
+pub const SELECTOR_DEPOSIT_OF: u32 = 0x0cd8c00a;
+/// `referendumCount()`
+pub const SELECTOR_REFERENDUM_COUNT: u32 = 0x3a42ee31;
+/// `referendumInfo(uint32)`
+pub const SELECTOR_REFERENDUM_INFO: u32 = 0x44c4a2a0;
+/// `referendumTally(uint32)`
+pub const SELECTOR_REFERENDUM_TALLY: u32 = 0x61b95999;
+
+/// The governance precompile.
+pub struct Governance<R>(PhantomData<R>);
+
+impl<R> Precompile for Governance<R>
+where
+	R: pallet_democracy::Config + pallet_elections_phragmen::Config + pallet_evm::Config,
+	R::Call: Dispatchable<PostInfo = PostDispatchInfo>
+		+ GetDispatchInfo
+		+ From<pallet_democracy::Call<R>>
+		+ From<pallet_elections_phragmen::Call<R>>,
+	<R::Call as Dispatchable>::Origin: From<Option<R::AccountId>>,
+{
+	fn execute(input: &[u8], target_gas: Option<u64>, context: &Context) -> EvmResult<PrecompileOutput> {
+		dispatch::ensure_direct_call(context, hash(2050))?;
+		let mut gasometer = Gasometer::new(target_gas);
+		let (selector, mut input) = EvmDataReader::new_with_selector(input)?;
+		let call: R::Call = match selector {
+			SELECTOR_PUBLIC_PROP_COUNT => {
+				gasometer.record_reads::<R>(1)?;
+				let count = pallet_democracy::Pallet::<R>::public_prop_count();
+				return Ok(dispatch::succeed(&gasometer, EvmDataWriter::new().write(count).build()));
+			}
+			SELECTOR_PUBLIC_PROP => return Self::public_prop(&mut gasometer, &mut input),
+			SELECTOR_DEPOSIT_OF => return Self::deposit_of(&mut gasometer, &mut input),
+			SELECTOR_REFERENDUM_COUNT => {
+				gasometer.record_reads::<R>(1)?;
+				let count = pallet_democracy::Pallet::<R>::referendum_count();
+				return Ok(dispatch::succeed(&gasometer, EvmDataWriter::new().write(count).build()));
+			}
+			SELECTOR_REFERENDUM_INFO => return Self::referendum_info(&mut gasometer, &mut input),
+			SELECTOR_REFERENDUM_TALLY => return Self::referendum_tally(&mut gasometer, &mut input),
+			SELECTOR_PROPOSE => {
+				let proposal_hash = input.read_decoded()?;
+				pallet_democracy::Call::<R>::propose(proposal_hash, abi::to_balance(input.read()?)?).into()
+			}
+			SELECTOR_SECOND => pallet_democracy::Call::<R>::second(input.read()?, input.read()?).into(),
+			SELECTOR_STANDARD_VOTE => {
+				let ref_index = input.read()?;
+				let aye = input.read()?;
+				let balance = abi::to_balance(input.read()?)?;
+				let conviction = read_conviction(&mut input)?;
+				let vote = AccountVote::Standard {
+					vote: Vote { aye, conviction },
+					balance,
+				};
+				pallet_democracy::Call::<R>::vote(ref_index, vote).into()
+			}
+			SELECTOR_REMOVE_VOTE => pallet_democracy::Call::<R>::remove_vote(input.read()?).into(),
+			SELECTOR_DELEGATE => {
+				let to = input.read_decoded()?;
+				let conviction = read_conviction(&mut input)?;
+				pallet_democracy::Call::<R>::delegate(to, conviction, abi::to_balance(input.read()?)?).into()
+			}
+			SELECTOR_UNDELEGATE => pallet_democracy::Call::<R>::undelegate().into(),
+			SELECTOR_VOTE_CANDIDATES => {
+				let candidates = input
+					.read::<Vec<H256>>()?
+					.into_iter()
+					.map(|candidate| EvmDataReader::new(candidate.as_bytes()).read_decoded())
+					.collect::<EvmResult<Vec<_>>>()?;
+				let value = abi::to_balance(input.read()?)?;
+				pallet_elections_phragmen::Call::<R>::vote(candidates, value).into()
+			}
+			SELECTOR_REMOVE_VOTER => pallet_elections_phragmen::Call::<R>::remove_voter().into(),
+			_ => return Err(error("unknown governance selector")),
+		};
+
+		dispatch::dispatch_as::<R>(&mut gasometer, context.caller, call)?;
+		Ok(dispatch::succeed(&gasometer, Vec::new()))
+	}
+}
+
+impl<R> Governance<R>
+where
+	R: pallet_democracy::Config + pallet_evm::Config,
+{
+	fn public_prop(gasometer: &mut Gasometer, input: &mut EvmDataReader) -> EvmResult<PrecompileOutput> {
+		let position: u32 = input.read()?;
+		gasometer.record_reads::<R>(1)?;
+		let props = pallet_democracy::Pallet::<R>::public_props();
+		let (index, proposal_hash, proposer) = props
+			.get(position as usize)
+			.ok_or_else(|| error("no public proposal at this position"))?;
+		let output = EvmDataWriter::new()
+			.write(*index)
+			.write_encoded(proposal_hash)
+			.write_encoded(proposer);
+		Ok(dispatch::succeed(gasometer, output.build()))
+	}
+
+	fn deposit_of(gasometer: &mut Gasometer, input: &mut EvmDataReader) -> EvmResult<PrecompileOutput> {
+		let index: u32 = input.read()?;
+		gasometer.record_reads::<R>(1)?;
+		let (deposit, seconds) = pallet_democracy::Pallet::<R>::deposit_of(index)
+			.map_or((U256::zero(), 0), |(backers, deposit)| {
+				(abi::from_balance(deposit), backers.len() as u32)
+			});
+		let output = EvmDataWriter::new().write(deposit).write(seconds);
+		Ok(dispatch::succeed(gasometer, output.build()))
+	}
+
+	fn referendum_info(gasometer: &mut Gasometer, input: &mut EvmDataReader) -> EvmResult<PrecompileOutput> {
+		let index: u32 = input.read()?;
+		gasometer.record_reads::<R>(1)?;
+		let output = match pallet_democracy::Pallet::<R>::referendum_info(index) {
+			Some(ReferendumInfo::Ongoing(status)) => {
+				let threshold: u8 = match status.threshold {
+					VoteThreshold::SuperMajorityApprove => 0,
+					VoteThreshold::SuperMajorityAgainst => 1,
+					VoteThreshold::SimpleMajority => 2,
+				};
+				EvmDataWriter::new()
+					.write(1u8)
+					.write(abi::from_block_number(status.end))
+					.write_encoded(&status.proposal_hash)
+					.write(threshold)
+					.write(abi::from_block_number(status.delay))
+			}
+			Some(ReferendumInfo::Finished { approved, end }) => EvmDataWriter::new()
+				.write(if approved { 2u8 } else { 3u8 })
+				.write(abi::from_block_number(end))
+				.write(H256::zero())
+				.write(0u8)
+				.write(U256::zero()),
+			None => EvmDataWriter::new()
+				.write(0u8)
+				.write(U256::zero())
+				.write(H256::zero())
+				.write(0u8)
+				.write(U256::zero()),
+		};
+		Ok(dispatch::succeed(gasometer, output.build()))
+	}
+
+	fn referendum_tally(gasometer: &mut Gasometer, input: &mut EvmDataReader) -> EvmResult<PrecompileOutput> {
+		let index: u32 = input.read()?;
+		gasometer.record_reads::<R>(1)?;
+		let tally = match pallet_democracy::Pallet::<R>::referendum_info(index) {
+			Some(ReferendumInfo::Ongoing(status)) => status.tally,
+			_ => return Err(error("referendum is not ongoing")),
+		};
+		let output = EvmDataWriter::new()
+			.write(abi::from_balance(tally.ayes))
+			.write(abi::from_balance(tally.nays))
+			.write(abi::from_balance(tally.turnout));
+		Ok(dispatch::succeed(gasometer, output.build()))
+	}
+}
+
+fn read_conviction(input: &mut EvmDataReader) -> EvmResult<Conviction> {
+	Conviction::try_from(input.read::<u8>()?).map_err(|_| error("unknown conviction"))
+}
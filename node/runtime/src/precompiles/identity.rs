@@ -0,0 +1,265 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Lets contracts read and manage on-chain identities in `pallet_identity`.
+//! Calls are dispatched with the caller's mapped account as the origin.
+//!
+//! Each identity field is the raw data stored for it, up to 32 bytes, and is
+//! empty if the field is not set. Fields set from Substrate as a hash of their
+//! data read as empty, as do the PGP fingerprint and additional fields, which
+//! `setIdentity` clears.
+//!
+//! ```solidity
+//! interface Identity {
+//!     struct Info {
+//!         bytes display;
+//!         bytes legal;
+//!         bytes web;
+//!         bytes riot;
+//!         bytes email;
+//!         bytes image;
+//!         bytes twitter;
+//!     }
+//!     // judgement: 0 = Unknown, 1 = FeePaid, 2 = Reasonable, 3 = KnownGood,
+//!     // 4 = OutOfDate, 5 = LowQuality, 6 = Erroneous
+//!     // fee: the fee paid, if the judgement is FeePaid
+//!     struct Judgement {
+//!         uint32 registrarIndex;
+//!         uint8 judgement;
+//!         uint256 fee;
+//!     }
+//!     function setIdentity(Info calldata info) external;
+//!     function clearIdentity() external;
+//!     function requestJudgement(uint32 registrarIndex, uint256 maxFee) external;
+//!     // Returns (hasIdentity, info, judgements)
+//!     function identityOfAccount(bytes32 account) external view returns (bool, Info memory, Judgement[] memory);
+//!     function identityOf(address account) external view returns (bool, Info memory, Judgement[] memory);
+//! }
+//! ```
+
+use super::{
+	abi::{self, error, Bytes, EvmData, EvmDataReader, EvmDataWriter, EvmResult},
+	dispatch::{self, Gasometer},
+	hash,
+};
+use evm::{executor::PrecompileOutput, Context};
+use frame_support::dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo};
+use pallet_evm::{AddressMapping, Precompile};
+use pallet_identity::{Data, IdentityInfo, Judgement};
+use sp_core::{H160, U256};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// `setIdentity((bytes,bytes,bytes,bytes,bytes,bytes,bytes))`
+pub const SELECTOR_SET_IDENTITY: u32 = 0x6af79c72;
+/// `clearIdentity()`
+pub const SELECTOR_CLEAR_IDENTITY: u32 = 0x7a6a10c7;
+/// `requestJudgement(uint32,uint256)`
+pub const SELECTOR_REQUEST_JUDGEMENT: u32 = 0xd523ceb9;
+/// `identityOfAccount(bytes32)`
+pub const SELECTOR_IDENTITY_OF_ACCOUNT: u32 = 0x10933323;
+/// `identityOf(address)`
+pub const SELECTOR_IDENTITY_OF: u32 = 0xc6345626;
+
+/// The most raw data an identity field can hold.
+const MAX_FIELD_LEN: usize = 32;
+
+/// The identity fields contracts can read and set, as the `Info` struct.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Info {
+	pub display: Bytes,
+	pub legal: Bytes,
+	pub web: Bytes,
+	pub riot: Bytes,
+	pub email: Bytes,
+	pub image: Bytes,
+	pub twitter: Bytes,
+}
+
+impl Info {
+	fn from_identity(info: &IdentityInfo) -> Self {
+		Info {
+			display: from_data(&info.display),
+			legal: from_data(&info.legal),
+			web: from_data(&info.web),
+			riot: from_data(&info.riot),
+			email: from_data(&info.email),
+			image: from_data(&info.image),
+			twitter: from_data(&info.twitter),
+		}
+	}
+
+	fn into_identity(self) -> EvmResult<IdentityInfo> {
+		Ok(IdentityInfo {
+			additional: Default::default(),
+			display: into_data(self.display)?,
+			legal: into_data(self.legal)?,
+			web: into_data(self.web)?,
+			riot: into_data(self.riot)?,
+			email: into_data(self.email)?,
+			pgp_fingerprint: None,
+			image: into_data(self.image)?,
+			twitter: into_data(self.twitter)?,
+		})
+	}
+}
+
+impl EvmData for Info {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		let mut members = reader.read_struct()?;
+		Ok(Info {
+			display: members.read()?,
+			legal: members.read()?,
+			web: members.read()?,
+			riot: members.read()?,
+			email: members.read()?,
+			image: members.read()?,
+			twitter: members.read()?,
+		})
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		let members = EvmDataWriter::new()
+			.write(value.display)
+			.write(value.legal)
+			.write(value.web)
+			.write(value.riot)
+			.write(value.email)
+			.write(value.image)
+			.write(value.twitter);
+		writer.write_struct(members)
+	}
+}
+
+/// A registrar's judgement of an identity, as the `Judgement` struct.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RegistrarJudgement {
+	pub registrar_index: u32,
+	pub judgement: u8,
+	pub fee: U256,
+}
+
+impl EvmData for RegistrarJudgement {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		Ok(RegistrarJudgement {
+			registrar_index: reader.read()?,
+			judgement: reader.read()?,
+			fee: reader.read()?,
+		})
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		u32::write(writer, value.registrar_index);
+		u8::write(writer, value.judgement);
+		U256::write(writer, value.fee);
+	}
+}
+
+/// The identity precompile.
+pub struct OnChainIdentity<R>(PhantomData<R>);
+
+impl<R> Precompile for OnChainIdentity<R>
+where
+	R: pallet_identity::Config + pallet_evm::Config,
+	R::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + From<pallet_identity::Call<R>>,
+	<R::Call as Dispatchable>::Origin: From<Option<R::AccountId>>,
+{
+	fn execute(input: &[u8], target_gas: Option<u64>, context: &Context) -> EvmResult<PrecompileOutput> {
+		dispatch::ensure_direct_call(context, hash(2051))?;
+		let mut gasometer = Gasometer::new(target_gas);
+		let (selector, mut input) = EvmDataReader::new_with_selector(input)?;
+		let call: R::Call = match selector {
+			SELECTOR_IDENTITY_OF_ACCOUNT => {
+				let who = input.read_decoded()?;
+				return Self::identity_of(&mut gasometer, who);
+			}
+			SELECTOR_IDENTITY_OF => {
+				let who = R::AddressMapping::into_account_id(input.read::<H160>()?);
+				return Self::identity_of(&mut gasometer, who);
+			}
+			SELECTOR_SET_IDENTITY => {
+				let info = input.read::<Info>()?.into_identity()?;
+				pallet_identity::Call::<R>::set_identity(info).into()
+			}
+			SELECTOR_CLEAR_IDENTITY => pallet_identity::Call::<R>::clear_identity().into(),
+			SELECTOR_REQUEST_JUDGEMENT => {
+				let registrar_index = input.read()?;
+				let max_fee = abi::to_balance(input.read()?)?;
+				pallet_identity::Call::<R>::request_judgement(registrar_index, max_fee).into()
+			}
+			_ => return Err(error("unknown identity selector")),
+		};
+
+		dispatch::dispatch_as::<R>(&mut gasometer, context.caller, call)?;
+		Ok(dispatch::succeed(&gasometer, Vec::new()))
+	}
+}
+
+impl<R> OnChainIdentity<R>
+where
+	R: pallet_identity::Config + pallet_evm::Config,
+{
+	fn identity_of(gasometer: &mut Gasometer, who: R::AccountId) -> EvmResult<PrecompileOutput> {
+		gasometer.record_reads::<R>(1)?;
+		let output = match pallet_identity::Pallet::<R>::identity(who) {
+			Some(registration) => {
+				let judgements = registration
+					.judgements
+					.iter()
+					.map(|(registrar_index, judgement)| {
+						let (judgement, fee) = match judgement {
+							Judgement::Unknown => (0, U256::zero()),
+							Judgement::FeePaid(fee) => (1, abi::from_balance(*fee)),
+							Judgement::Reasonable => (2, U256::zero()),
+							Judgement::KnownGood => (3, U256::zero()),
+							Judgement::OutOfDate => (4, U256::zero()),
+							Judgement::LowQuality => (5, U256::zero()),
+							Judgement::Erroneous => (6, U256::zero()),
+						};
+						RegistrarJudgement {
+							registrar_index: *registrar_index,
+							judgement,
+							fee,
+						}
+					})
+					.collect::<Vec<_>>();
+				EvmDataWriter::new()
+					.write(true)
+					.write(Info::from_identity(&registration.info))
+					.write(judgements)
+			}
+			None => EvmDataWriter::new()
+				.write(false)
+				.write(Info::default())
+				.write(Vec::<RegistrarJudgement>::new()),
+		};
+		Ok(dispatch::succeed(gasometer, output.build()))
+	}
+}
+
+fn from_data(data: &Data) -> Bytes {
+	match data {
+		Data::Raw(bytes) => Bytes(bytes.to_vec()),
+		_ => Bytes::default(),
+	}
+}
+
+fn into_data(bytes: Bytes) -> EvmResult<Data> {
+	match bytes.0.len() {
+		0 => Ok(Data::None),
+		len if len <= MAX_FIELD_LEN => Ok(Data::Raw(bytes.0)),
+		_ => Err(error("identity field is longer than 32 bytes")),
+	}
+}
@@ -0,0 +1,551 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A mock runtime for testing the precompiles.
+
+use super::EvmExtension;
+use codec::{Decode, Encode};
+use frame_election_provider_support::onchain;
+use frame_support::{
+	construct_runtime, parameter_types,
+	traits::{InstanceFilter, LockIdentifier, MaxEncodedLen, U128CurrencyToVote},
+	weights::{RuntimeDbWeight, Weight},
+};
+use frame_system::{
+	mocking::{MockBlock, MockUncheckedExtrinsic},
+	EnsureRoot, EnsureSigned,
+};
+use pallet_evm::{AddressMapping, EnsureAddressNever, EnsureAddressRoot, FeeCalculator, HashedAddressMapping};
+use pallet_staking::{EraIndex, EraPayout, StakerStatus};
+use sp_core::{H160, H256, U256};
+use sp_runtime::{
+	testing::{Header, UintAuthorityId},
+	traits::{BlakeTwo256, Convert, IdentityLookup},
+	AccountId32, Perbill, RuntimeDebug,
+};
+use sp_staking::SessionIndex;
+
+pub type AccountId = AccountId32;
+pub type Balance = u128;
+pub type BlockNumber = u64;
+
+type UncheckedExtrinsic = MockUncheckedExtrinsic<Test>;
+type Block = MockBlock<Test>;
+
+construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Utility: pallet_utility::{Pallet, Call, Event},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
+		EVM: pallet_evm::{Pallet, Call, Storage, Event<T>},
+		Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>},
+		Democracy: pallet_democracy::{Pallet, Call, Storage, Event<T>},
+		PhragmenElection: pallet_elections_phragmen::{Pallet, Call, Storage, Event<T>},
+		Identity: pallet_identity::{Pallet, Call, Storage, Event<T>},
+		Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>},
+		Multisig: pallet_multisig::{Pallet, Call, Storage, Event<T>},
+		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Call, Storage},
+		Contracts: pallet_contracts::{Pallet, Call, Storage, Event<T>},
+		EvmDispatch: evm_dispatch::{Pallet, Event<T>},
+		EvmAllowances: evm_allowances::{Pallet, Storage},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+		Staking: pallet_staking::{Pallet, Call, Config<T>, Storage, Event<T>},
+		Session: pallet_session::{Pallet, Call, Storage, Event, Config<T>},
+		Historical: pallet_session::historical::{Pallet},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+	pub const DbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 10, write: 100 };
+}
+
+impl frame_system::Config for Test {
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type AccountId = AccountId;
+	type BaseCallFilter = ();
+	type BlockHashCount = BlockHashCount;
+	type BlockLength = ();
+	type BlockNumber = BlockNumber;
+	type BlockWeights = ();
+	type Call = Call;
+	type DbWeight = DbWeight;
+	type Event = Event;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type Header = Header;
+	type Index = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type OnKilledAccount = ();
+	type OnNewAccount = ();
+	type OnSetCode = ();
+	type Origin = Origin;
+	type PalletInfo = PalletInfo;
+	type SS58Prefix = SS58Prefix;
+	type SystemWeightInfo = ();
+	type Version = ();
+}
+
+impl pallet_utility::Config for Test {
+	type Call = Call;
+	type Event = Event;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+	pub const MaxLocks: u32 = 50;
+}
+
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+	type Balance = Balance;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type MaxLocks = MaxLocks;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 5;
+}
+
+impl pallet_timestamp::Config for Test {
+	type MinimumPeriod = MinimumPeriod;
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type WeightInfo = ();
+}
+
+/// Charges nothing for gas.
+pub struct FixedGasPrice;
+impl FeeCalculator for FixedGasPrice {
+	fn min_gas_price() -> U256 {
+		U256::zero()
+	}
+}
+
+parameter_types! {
+	pub const ChainId: u64 = 42;
+	pub BlockGasLimit: U256 = U256::from(u32::max_value());
+}
+
+impl pallet_evm::Config for Test {
+	type AddressMapping = HashedAddressMapping<BlakeTwo256>;
+	type BlockGasLimit = BlockGasLimit;
+	type CallOrigin = EnsureAddressRoot<AccountId>;
+	type ChainId = ChainId;
+	type Currency = Balances;
+	type Event = Event;
+	type FeeCalculator = FixedGasPrice;
+	type GasWeightMapping = ();
+	type OnChargeTransaction = ();
+	type Precompiles = ();
+	type Runner = pallet_evm::runner::stack::Runner<Self>;
+	type WithdrawOrigin = EnsureAddressNever<AccountId>;
+}
+
+parameter_types! {
+	pub const MaximumSchedulerWeight: Weight = 1_000_000_000;
+	pub const MaxScheduledPerBlock: u32 = 50;
+}
+
+impl pallet_scheduler::Config for Test {
+	type Call = Call;
+	type Event = Event;
+	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type Origin = Origin;
+	type PalletsOrigin = OriginCaller;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const LaunchPeriod: BlockNumber = 10;
+	pub const VotingPeriod: BlockNumber = 10;
+	pub const FastTrackVotingPeriod: BlockNumber = 2;
+	pub const MinimumDeposit: Balance = 10;
+	pub const InstantAllowed: bool = false;
+	pub const EnactmentPeriod: BlockNumber = 2;
+	pub const CooloffPeriod: BlockNumber = 2;
+	pub const PreimageByteDeposit: Balance = 1;
+	pub const MaxVotes: u32 = 100;
+	pub const MaxProposals: u32 = 100;
+}
+
+impl pallet_democracy::Config for Test {
+	type BlacklistOrigin = EnsureRoot<AccountId>;
+	type CancelProposalOrigin = EnsureRoot<AccountId>;
+	type CancellationOrigin = EnsureRoot<AccountId>;
+	type CooloffPeriod = CooloffPeriod;
+	type Currency = Balances;
+	type EnactmentPeriod = EnactmentPeriod;
+	type Event = Event;
+	type ExternalDefaultOrigin = EnsureRoot<AccountId>;
+	type ExternalMajorityOrigin = EnsureRoot<AccountId>;
+	type ExternalOrigin = EnsureRoot<AccountId>;
+	type FastTrackOrigin = EnsureRoot<AccountId>;
+	type FastTrackVotingPeriod = FastTrackVotingPeriod;
+	type InstantAllowed = InstantAllowed;
+	type InstantOrigin = EnsureRoot<AccountId>;
+	type LaunchPeriod = LaunchPeriod;
+	type MaxProposals = MaxProposals;
+	type MaxVotes = MaxVotes;
+	type MinimumDeposit = MinimumDeposit;
+	type OperationalPreimageOrigin = EnsureSigned<AccountId>;
+	type PalletsOrigin = OriginCaller;
+	type PreimageByteDeposit = PreimageByteDeposit;
+	type Proposal = Call;
+	type Scheduler = Scheduler;
+	type Slash = ();
+	type VetoOrigin = EnsureSigned<AccountId>;
+	type VotingPeriod = VotingPeriod;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const CandidacyBond: Balance = 10;
+	pub const VotingBondBase: Balance = 2;
+	pub const VotingBondFactor: Balance = 1;
+	pub const DesiredMembers: u32 = 2;
+	pub const DesiredRunnersUp: u32 = 2;
+	pub const TermDuration: BlockNumber = 10;
+	pub const ElectionsPhragmenPalletId: LockIdentifier = *b"phrelect";
+}
+
+impl pallet_elections_phragmen::Config for Test {
+	type CandidacyBond = CandidacyBond;
+	type ChangeMembers = ();
+	type Currency = Balances;
+	type CurrencyToVote = U128CurrencyToVote;
+	type DesiredMembers = DesiredMembers;
+	type DesiredRunnersUp = DesiredRunnersUp;
+	type Event = Event;
+	type InitializeMembers = ();
+	type KickedMember = ();
+	type LoserCandidate = ();
+	type PalletId = ElectionsPhragmenPalletId;
+	type TermDuration = TermDuration;
+	type VotingBondBase = VotingBondBase;
+	type VotingBondFactor = VotingBondFactor;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const BasicDeposit: Balance = 10;
+	pub const FieldDeposit: Balance = 1;
+	pub const SubAccountDeposit: Balance = 10;
+	pub const MaxSubAccounts: u32 = 2;
+	pub const MaxAdditionalFields: u32 = 2;
+	pub const MaxRegistrars: u32 = 4;
+}
+
+impl pallet_identity::Config for Test {
+	type BasicDeposit = BasicDeposit;
+	type Currency = Balances;
+	type Event = Event;
+	type FieldDeposit = FieldDeposit;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type MaxAdditionalFields = MaxAdditionalFields;
+	type MaxRegistrars = MaxRegistrars;
+	type MaxSubAccounts = MaxSubAccounts;
+	type RegistrarOrigin = EnsureRoot<AccountId>;
+	type Slashed = ();
+	type SubAccountDeposit = SubAccountDeposit;
+	type WeightInfo = ();
+}
+
+/// Mirrors the first three of the runtime's proxy types.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, RuntimeDebug)]
+pub enum ProxyType {
+	Any = 0,
+	NonTransfer = 1,
+	Governance = 2,
+}
+
+impl Default for ProxyType {
+	fn default() -> Self {
+		Self::Any
+	}
+}
+
+impl MaxEncodedLen for ProxyType {
+	fn max_encoded_len() -> usize {
+		1
+	}
+}
+
+impl InstanceFilter<Call> for ProxyType {
+	fn filter(&self, call: &Call) -> bool {
+		match self {
+			ProxyType::Any => true,
+			ProxyType::NonTransfer => !matches!(call, Call::Balances(..)),
+			ProxyType::Governance => matches!(call, Call::Democracy(..) | Call::PhragmenElection(..)),
+		}
+	}
+
+	fn is_superset(&self, other: &Self) -> bool {
+		self == &ProxyType::Any || self == other
+	}
+}
+
+parameter_types! {
+	pub const ProxyDepositBase: Balance = 10;
+	pub const ProxyDepositFactor: Balance = 1;
+	pub const MaxProxies: u16 = 4;
+	pub const MaxPending: u16 = 4;
+	pub const AnnouncementDepositBase: Balance = 10;
+	pub const AnnouncementDepositFactor: Balance = 1;
+}
+
+impl pallet_proxy::Config for Test {
+	type AnnouncementDepositBase = AnnouncementDepositBase;
+	type AnnouncementDepositFactor = AnnouncementDepositFactor;
+	type Call = Call;
+	type CallHasher = BlakeTwo256;
+	type Currency = Balances;
+	type Event = Event;
+	type MaxPending = MaxPending;
+	type MaxProxies = MaxProxies;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type ProxyType = ProxyType;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MultisigDepositBase: Balance = 10;
+	pub const MultisigDepositFactor: Balance = 1;
+	pub const MaxSignatories: u16 = 4;
+}
+
+impl pallet_multisig::Config for Test {
+	type Call = Call;
+	type Currency = Balances;
+	type DepositBase = MultisigDepositBase;
+	type DepositFactor = MultisigDepositFactor;
+	type Event = Event;
+	type MaxSignatories = MaxSignatories;
+	type WeightInfo = ();
+}
+
+impl pallet_randomness_collective_flip::Config for Test {}
+
+parameter_types! {
+	pub const SignedClaimHandicap: BlockNumber = 2;
+	pub const TombstoneDeposit: Balance = 16;
+	pub const DepositPerContract: Balance = 1;
+	pub const DepositPerStorageByte: Balance = 1;
+	pub const DepositPerStorageItem: Balance = 1;
+	pub const RentFraction: Perbill = Perbill::from_percent(1);
+	pub const SurchargeReward: Balance = 1;
+	pub const DeletionQueueDepth: u32 = 1024;
+	pub const DeletionWeightLimit: Weight = 500_000_000_000;
+	pub ContractsSchedule: pallet_contracts::Schedule<Test> = Default::default();
+}
+
+/// Prices weight at one unit of balance each.
+impl Convert<Weight, Balance> for Test {
+	fn convert(weight: Weight) -> Balance {
+		weight.into()
+	}
+}
+
+impl pallet_contracts::Config for Test {
+	type CallStack = [pallet_contracts::Frame<Self>; 31];
+	type ChainExtension = EvmExtension<Self>;
+	type Currency = Balances;
+	type DeletionQueueDepth = DeletionQueueDepth;
+	type DeletionWeightLimit = DeletionWeightLimit;
+	type DepositPerContract = DepositPerContract;
+	type DepositPerStorageByte = DepositPerStorageByte;
+	type DepositPerStorageItem = DepositPerStorageItem;
+	type Event = Event;
+	type Randomness = RandomnessCollectiveFlip;
+	type RentFraction = RentFraction;
+	type RentPayment = ();
+	type Schedule = ContractsSchedule;
+	type SignedClaimHandicap = SignedClaimHandicap;
+	type SurchargeReward = SurchargeReward;
+	type Time = Timestamp;
+	type TombstoneDeposit = TombstoneDeposit;
+	type WeightInfo = ();
+	type WeightPrice = Self;
+}
+
+/// Allows `System::remark` and `Democracy`.
+#[derive(Clone, Copy, Default)]
+pub struct DispatchFilter;
+impl InstanceFilter<Call> for DispatchFilter {
+	fn filter(&self, call: &Call) -> bool {
+		matches!(call, Call::System(frame_system::Call::remark(..)) | Call::Democracy(..))
+	}
+}
+
+impl evm_dispatch::Config for Test {
+	type CallFilter = DispatchFilter;
+	type Event = Event;
+}
+
+impl evm_allowances::Config for Test {}
+
+parameter_types! {
+	pub const AssetDeposit: Balance = 100;
+	pub const ApprovalDeposit: Balance = 1;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: Balance = 10;
+	pub const MetadataDepositPerByte: Balance = 1;
+}
+
+impl pallet_assets::Config for Test {
+	type ApprovalDeposit = ApprovalDeposit;
+	type AssetDeposit = AssetDeposit;
+	type AssetId = u32;
+	type Balance = u64;
+	type Currency = Balances;
+	type Event = Event;
+	type Extra = ();
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type Freezer = ();
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type StringLimit = StringLimit;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const Period: BlockNumber = 1;
+	pub const Offset: BlockNumber = 0;
+	pub const DisabledValidatorsThreshold: Perbill = Perbill::from_percent(17);
+}
+
+impl pallet_session::Config for Test {
+	type DisabledValidatorsThreshold = DisabledValidatorsThreshold;
+	type Event = Event;
+	type Keys = UintAuthorityId;
+	type NextSessionRotation = pallet_session::PeriodicSessions<Period, Offset>;
+	type SessionHandler = pallet_session::TestSessionHandler;
+	type SessionManager = pallet_session::historical::NoteHistoricalRoot<Self, Staking>;
+	type ShouldEndSession = pallet_session::PeriodicSessions<Period, Offset>;
+	type ValidatorId = AccountId;
+	type ValidatorIdOf = pallet_staking::StashOf<Self>;
+	type WeightInfo = ();
+}
+
+impl pallet_session::historical::Config for Test {
+	type FullIdentification = pallet_staking::Exposure<AccountId, Balance>;
+	type FullIdentificationOf = pallet_staking::ExposureOf<Self>;
+}
+
+impl onchain::Config for Test {
+	type AccountId = AccountId;
+	type Accuracy = Perbill;
+	type BlockNumber = BlockNumber;
+	type BlockWeights = ();
+	type DataProvider = Staking;
+}
+
+/// Pays validators 1_000 an era, however long it lasted.
+pub struct FixedEraPayout;
+impl EraPayout<Balance> for FixedEraPayout {
+	fn era_payout(_total_staked: Balance, _total_issuance: Balance, _era_duration_millis: u64) -> (Balance, Balance) {
+		(1_000, 0)
+	}
+}
+
+parameter_types! {
+	pub const SessionsPerEra: SessionIndex = 1;
+	pub const BondingDuration: EraIndex = 2;
+	pub const SlashDeferDuration: EraIndex = 0;
+	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+}
+
+impl pallet_staking::Config for Test {
+	type BondingDuration = BondingDuration;
+	type Currency = Balances;
+	type CurrencyToVote = U128CurrencyToVote;
+	type ElectionProvider = onchain::OnChainSequentialPhragmen<Self>;
+	type EraPayout = FixedEraPayout;
+	type Event = Event;
+	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+	type NextNewSession = Session;
+	type Reward = ();
+	type RewardRemainder = ();
+	type SessionInterface = Self;
+	type SessionsPerEra = SessionsPerEra;
+	type Slash = ();
+	type SlashCancelOrigin = EnsureRoot<AccountId>;
+	type SlashDeferDuration = SlashDeferDuration;
+	type UnixTime = Timestamp;
+	type WeightInfo = ();
+
+	const MAX_NOMINATIONS: u32 = 16;
+}
+
+pub const ALICE: H160 = H160([0xaa; 20]);
+pub const BOB: H160 = H160([0xbb; 20]);
+
+/// The stash and controller of the only validator.
+pub fn validator() -> AccountId {
+	AccountId32::new([1; 32])
+}
+
+/// The account an EVM address is mapped to.
+pub fn account(address: H160) -> AccountId {
+	<Test as pallet_evm::Config>::AddressMapping::into_account_id(address)
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![
+			(account(ALICE), 1_000_000),
+			(account(BOB), 1_000_000),
+			(validator(), 1_000_000),
+		],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	pallet_staking::GenesisConfig::<Test> {
+		stakers: vec![(validator(), validator(), 1_000, StakerStatus::Validator)],
+		validator_count: 1,
+		minimum_validator_count: 1,
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	// Needs the staking genesis, as it asks `Staking` for the first validators.
+	pallet_session::GenesisConfig::<Test> {
+		keys: vec![(validator(), validator(), UintAuthorityId(1))],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
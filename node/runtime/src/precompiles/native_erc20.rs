@@ -0,0 +1,80 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The ERC-20 interface to the native EDG balance, so that contracts can use
+//! EDG without a wrapped token.
+
+use super::{
+	abi::{self, EvmResult},
+	erc20::{self, Erc20Token},
+	hash,
+};
+use evm::{executor::PrecompileOutput, Context};
+use frame_support::{
+	traits::{Currency, ExistenceRequirement, Get},
+	weights::Weight,
+};
+use pallet_evm::{AddressMapping, Precompile};
+use sp_core::{H160, U256};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// The ERC-20 precompile for the native balance.
+pub struct NativeErc20<R>(PhantomData<R>);
+
+impl<R: pallet_evm::Config> Erc20Token for NativeErc20<R> {
+	fn name(&self) -> Vec<u8> {
+		b"Edgeware".to_vec()
+	}
+
+	fn symbol(&self) -> Vec<u8> {
+		b"EDG".to_vec()
+	}
+
+	fn decimals(&self) -> u8 {
+		18
+	}
+
+	fn total_supply(&self) -> U256 {
+		abi::from_balance(R::Currency::total_issuance())
+	}
+
+	fn balance_of(&self, who: H160) -> U256 {
+		abi::from_balance(R::Currency::free_balance(&R::AddressMapping::into_account_id(who)))
+	}
+
+	fn transfer(&self, from: H160, to: H160, amount: U256) -> EvmResult {
+		R::Currency::transfer(
+			&R::AddressMapping::into_account_id(from),
+			&R::AddressMapping::into_account_id(to),
+			abi::to_balance(amount)?,
+			ExistenceRequirement::AllowDeath,
+		)
+		.map_err(|_| abi::error("transfer failed"))
+	}
+
+	fn transfer_weight(&self) -> Weight {
+		R::DbWeight::get().reads_writes(2, 2)
+	}
+}
+
+impl<R> Precompile for NativeErc20<R>
+where
+	R: pallet_evm::Config + evm_allowances::Config,
+{
+	fn execute(input: &[u8], target_gas: Option<u64>, context: &Context) -> EvmResult<PrecompileOutput> {
+		erc20::execute::<R, _>(&NativeErc20::<R>(PhantomData), hash(2048), input, target_gas, context)
+	}
+}
@@ -0,0 +1,258 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Lets contracts manage proxies with `pallet_proxy` and approve multisig
+//! calls with `pallet_multisig`. Calls are dispatched with the caller's
+//! mapped account as the origin, and accounts are passed as `bytes32`.
+//!
+//! Proxied calls are SCALE-encoded runtime calls. They must pass
+//! `evm_dispatch`'s filter, as calls through the Dispatch precompile do, and
+//! are then limited by the proxy's type exactly as for Substrate callers, as
+//! `pallet_proxy` applies that filter itself. So must the calls multisig
+//! approvals execute: the call given to `asMulti`, and the stored call an
+//! `approveAsMulti` could complete.
+//!
+//! ```solidity
+//! interface ProxyMultisig {
+//!     // A multisig timepoint. (0, 0) means none, for the first approval.
+//!     struct Timepoint {
+//!         uint32 height;
+//!         uint32 index;
+//!     }
+//!     // proxyType: 0 = Any, 1 = NonTransfer, 2 = Governance, 3 = Staking
+//!     function addProxy(bytes32 delegate, uint8 proxyType, uint32 delay) external;
+//!     function removeProxy(bytes32 delegate, uint8 proxyType, uint32 delay) external;
+//!     function proxy(bytes32 real, bytes calldata call) external;
+//!     // otherSignatories must be sorted
+//!     function asMulti(
+//!         uint16 threshold,
+//!         bytes32[] calldata otherSignatories,
+//!         Timepoint calldata timepoint,
+//!         bytes calldata call,
+//!         bool storeCall,
+//!         uint64 maxWeight
+//!     ) external;
+//!     function approveAsMulti(
+//!         uint16 threshold,
+//!         bytes32[] calldata otherSignatories,
+//!         Timepoint calldata timepoint,
+//!         bytes32 callHash,
+//!         uint64 maxWeight
+//!     ) external;
+//!     function cancelAsMulti(
+//!         uint16 threshold,
+//!         bytes32[] calldata otherSignatories,
+//!         Timepoint calldata timepoint,
+//!         bytes32 callHash
+//!     ) external;
+//! }
+//! ```
+
+use super::{
+	abi::{error, Bytes, EvmData, EvmDataReader, EvmDataWriter, EvmResult},
+	dispatch::{self, Gasometer, MAX_CALL_DEPTH},
+	hash,
+};
+use codec::{Decode, DecodeLimit};
+use evm::{executor::PrecompileOutput, Context};
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	storage::migration::get_storage_value,
+	traits::{Get, GetCallMetadata, PalletInfo},
+};
+use pallet_evm::{AddressMapping, Precompile};
+use pallet_multisig::Timepoint;
+use sp_core::{H160, H256};
+use sp_std::{boxed::Box, marker::PhantomData, vec::Vec};
+
+/// `addProxy(bytes32,uint8,uint32)`
+pub const SELECTOR_ADD_PROXY: u32 = 0x0cadeda5;
+/// `removeProxy(bytes32,uint8,uint32)`
+pub const SELECTOR_REMOVE_PROXY: u32 = 0xcc4b0a1b;
+/// `proxy(bytes32,bytes)`
+pub const SELECTOR_PROXY: u32 = 0xc09c1b23;
+/// `asMulti(uint16,bytes32[],(uint32,uint32),bytes,bool,uint64)`
+pub const SELECTOR_AS_MULTI: u32 = 0x545e77e5;
+/// `approveAsMulti(uint16,bytes32[],(uint32,uint32),bytes32,uint64)`
+pub const SELECTOR_APPROVE_AS_MULTI: u32 = 0xbeae3126;
+/// `cancelAsMulti(uint16,bytes32[],(uint32,uint32),bytes32)`
+pub const SELECTOR_CANCEL_AS_MULTI: u32 = 0xcc52e9c5;
+
+/// A multisig timepoint, as the `Timepoint` struct.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TimepointData {
+	pub height: u32,
+	pub index: u32,
+}
+
+impl TimepointData {
+	fn into_timepoint<N: From<u32>>(self) -> Option<Timepoint<N>> {
+		if self == Self::default() {
+			return None;
+		}
+		Some(Timepoint {
+			height: self.height.into(),
+			index: self.index,
+		})
+	}
+}
+
+impl EvmData for TimepointData {
+	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+		Ok(TimepointData {
+			height: reader.read()?,
+			index: reader.read()?,
+		})
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		u32::write(writer, value.height);
+		u32::write(writer, value.index);
+	}
+}
+
+/// The proxy and multisig precompile.
+pub struct ProxyMultisig<R>(PhantomData<R>);
+
+impl<R> Precompile for ProxyMultisig<R>
+where
+	R: pallet_proxy::Config + pallet_multisig::Config + pallet_evm::Config + evm_dispatch::Config,
+	<R as frame_system::Config>::Call: Dispatchable<PostInfo = PostDispatchInfo>
+		+ GetDispatchInfo
+		+ GetCallMetadata
+		+ Decode
+		+ From<pallet_proxy::Call<R>>
+		+ From<pallet_multisig::Call<R>>,
+	<<R as frame_system::Config>::Call as Dispatchable>::Origin: From<Option<R::AccountId>>,
+{
+	fn execute(input: &[u8], target_gas: Option<u64>, context: &Context) -> EvmResult<PrecompileOutput> {
+		dispatch::ensure_direct_call(context, hash(2052))?;
+		let mut gasometer = Gasometer::new(target_gas);
+		let (selector, mut input) = EvmDataReader::new_with_selector(input)?;
+		let call: <R as frame_system::Config>::Call = match selector {
+			SELECTOR_ADD_PROXY => {
+				let delegate = input.read_decoded()?;
+				let proxy_type = read_proxy_type::<R>(&mut input)?;
+				let delay = input.read::<u32>()?.into();
+				pallet_proxy::Call::<R>::add_proxy(delegate, proxy_type, delay).into()
+			}
+			SELECTOR_REMOVE_PROXY => {
+				let delegate = input.read_decoded()?;
+				let proxy_type = read_proxy_type::<R>(&mut input)?;
+				let delay = input.read::<u32>()?.into();
+				pallet_proxy::Call::<R>::remove_proxy(delegate, proxy_type, delay).into()
+			}
+			SELECTOR_PROXY => {
+				let real = input.read_decoded()?;
+				let Bytes(call) = input.read()?;
+				let call = allowed_call::<R>(&mut gasometer, context.caller, &call)?;
+				pallet_proxy::Call::<R>::proxy(real, None, Box::new(call.into())).into()
+			}
+			SELECTOR_AS_MULTI => {
+				let threshold = input.read()?;
+				let other_signatories = read_accounts::<R>(&mut input)?;
+				let timepoint = input.read::<TimepointData>()?.into_timepoint();
+				let Bytes(call) = input.read()?;
+				allowed_call::<R>(&mut gasometer, context.caller, &call)?;
+				let store_call = input.read()?;
+				let max_weight = input.read()?;
+				pallet_multisig::Call::<R>::as_multi(
+					threshold,
+					other_signatories,
+					timepoint,
+					call,
+					store_call,
+					max_weight,
+				)
+				.into()
+			}
+			SELECTOR_APPROVE_AS_MULTI => {
+				let threshold = input.read()?;
+				let other_signatories = read_accounts::<R>(&mut input)?;
+				let timepoint = input.read::<TimepointData>()?.into_timepoint();
+				let call_hash = input.read::<H256>()?.0;
+				let max_weight = input.read()?;
+				gasometer.record_reads::<R>(1)?;
+				if let Some(call) = stored_multisig_call::<R>(&call_hash) {
+					allowed_call::<R>(&mut gasometer, context.caller, &call)?;
+				}
+				pallet_multisig::Call::<R>::approve_as_multi(
+					threshold,
+					other_signatories,
+					timepoint,
+					call_hash,
+					max_weight,
+				)
+				.into()
+			}
+			SELECTOR_CANCEL_AS_MULTI => {
+				let threshold = input.read()?;
+				let other_signatories = read_accounts::<R>(&mut input)?;
+				let timepoint = input
+					.read::<TimepointData>()?
+					.into_timepoint()
+					.ok_or_else(|| error("a timepoint is required"))?;
+				let call_hash = input.read::<H256>()?.0;
+				pallet_multisig::Call::<R>::cancel_as_multi(threshold, other_signatories, timepoint, call_hash).into()
+			}
+			_ => return Err(error("unknown proxy selector")),
+		};
+
+		dispatch::dispatch_as::<R>(&mut gasometer, context.caller, call)?;
+		Ok(dispatch::succeed(&gasometer, Vec::new()))
+	}
+}
+
+/// Decodes a runtime call to be dispatched on behalf of `caller`, failing if
+/// `evm_dispatch`'s filter does not allow it. A rejected call is recorded as
+/// the Dispatch precompile records it.
+fn allowed_call<R>(gasometer: &mut Gasometer, caller: H160, call: &[u8]) -> EvmResult<<R as frame_system::Config>::Call>
+where
+	R: evm_dispatch::Config + pallet_evm::Config,
+	<R as frame_system::Config>::Call: GetCallMetadata + Decode,
+{
+	let call = <R as frame_system::Config>::Call::decode_with_depth_limit(MAX_CALL_DEPTH, &mut &*call)
+		.map_err(|_| error("call could not be decoded"))?;
+	if let Some(rejected) = evm_dispatch::Pallet::<R>::rejected_call(&call) {
+		// The event is a storage write.
+		gasometer.record_weight::<R>(R::DbWeight::get().writes(1))?;
+		evm_dispatch::Pallet::<R>::note_rejected(R::AddressMapping::into_account_id(caller), rejected);
+		return Err(dispatch::not_allowed(rejected));
+	}
+	Ok(call)
+}
+
+/// The call stored with `pallet_multisig` under `call_hash`, if any. The
+/// approval that completes the multisig executes it.
+fn stored_multisig_call<R: pallet_multisig::Config>(call_hash: &[u8; 32]) -> Option<Vec<u8>> {
+	let pallet = <R as frame_system::Config>::PalletInfo::name::<pallet_multisig::Pallet<R>>()?;
+	// Calls are stored with their depositor and deposit, after the call itself.
+	get_storage_value::<Vec<u8>>(pallet.as_bytes(), b"Calls", call_hash)
+}
+
+/// Reads a proxy type as its index in the runtime's `ProxyType`.
+fn read_proxy_type<R: pallet_proxy::Config>(input: &mut EvmDataReader) -> EvmResult<R::ProxyType> {
+	let index: u8 = input.read()?;
+	R::ProxyType::decode(&mut &[index][..]).map_err(|_| error("unknown proxy type"))
+}
+
+fn read_accounts<R: frame_system::Config>(input: &mut EvmDataReader) -> EvmResult<Vec<R::AccountId>> {
+	input
+		.read::<Vec<H256>>()?
+		.into_iter()
+		.map(|account| EvmDataReader::new(account.as_bytes()).read_decoded())
+		.collect()
+}
@@ -0,0 +1,84 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Gives contracts the output of `pallet_randomness_collective_flip` for a
+//! subject.
+//!
+//! **The value is predictable.** It is a hash of the subject and the hashes of
+//! the last 81 blocks, so anyone can compute it before the calling transaction
+//! is included, and a block author can choose whether to produce a block for
+//! a value it does not like. It is fit for spreading out choices that nobody
+//! gains from influencing, not for lotteries or games with value at stake,
+//! which need a commit-reveal scheme or an oracle.
+//!
+//! ```solidity
+//! interface Randomness {
+//!     // Returns (randomness, blockNumber): the value for `subject`, and the
+//!     // block since which anyone could have known it
+//!     function random(bytes calldata subject) external view returns (bytes32, uint32);
+//! }
+//! ```
+
+use super::{
+	abi::{self, error, Bytes, EvmDataReader, EvmDataWriter, EvmResult, WORD},
+	dispatch::{self, Gasometer},
+};
+use evm::{executor::PrecompileOutput, Context};
+use frame_support::traits::Randomness as RandomnessT;
+use pallet_evm::Precompile;
+use sp_std::marker::PhantomData;
+
+/// `random(bytes)`
+pub const SELECTOR_RANDOM: u32 = 0x414bdd34;
+
+/// How many block hashes `pallet_randomness_collective_flip` hashes the subject
+/// with.
+const RANDOM_MATERIAL_LEN: u64 = 81;
+/// Gas for hashing, as the EVM charges for `SHA3`.
+const HASH_COST: u64 = 30;
+/// Gas per word hashed.
+const HASH_WORD_COST: u64 = 6;
+
+/// The randomness precompile.
+pub struct Randomness<R>(PhantomData<R>);
+
+impl<R> Precompile for Randomness<R>
+where
+	R: pallet_randomness_collective_flip::Config + pallet_evm::Config,
+{
+	fn execute(input: &[u8], target_gas: Option<u64>, _context: &Context) -> EvmResult<PrecompileOutput> {
+		let mut gasometer = Gasometer::new(target_gas);
+		let (selector, mut input) = EvmDataReader::new_with_selector(input)?;
+		match selector {
+			SELECTOR_RANDOM => {
+				let Bytes(subject) = input.read()?;
+				// The subject is hashed once with each block hash and its index.
+				let words = (subject.len() + 2 * WORD + WORD - 1) / WORD;
+				gasometer.record_cost(
+					RANDOM_MATERIAL_LEN
+						.saturating_mul(HASH_COST.saturating_add(HASH_WORD_COST.saturating_mul(words as u64))),
+				)?;
+				gasometer.record_reads::<R>(1)?;
+				let (randomness, block_number) = pallet_randomness_collective_flip::Pallet::<R>::random(&subject);
+				let output = EvmDataWriter::new()
+					.write_encoded(&randomness)
+					.write(abi::from_block_number(block_number));
+				Ok(dispatch::succeed(&gasometer, output.build()))
+			}
+			_ => Err(error("unknown randomness selector")),
+		}
+	}
+}
@@ -0,0 +1,177 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Lets contracts take part in `pallet_staking`. Calls are dispatched with the
+//! caller's mapped account as the stash or controller, and accounts are passed
+//! as `bytes32`.
+//!
+//! ```solidity
+//! interface Staking {
+//!     // payee: 0 = Staked, 1 = Stash, 2 = Controller
+//!     function bond(bytes32 controller, uint256 value, uint8 payee) external;
+//!     function bondExtra(uint256 value) external;
+//!     function unbond(uint256 value) external;
+//!     function withdrawUnbonded(uint32 numSlashingSpans) external;
+//!     function nominate(bytes32[] calldata targets) external;
+//!     function chill() external;
+//!     function payoutStakers(bytes32 validatorStash, uint32 era) external;
+//!     // Returns (stash, total, active, unlocking)
+//!     function ledger(bytes32 controller) external view returns (bytes32, uint256, uint256, uint256);
+//!     function bonded(bytes32 stash) external view returns (bytes32);
+//!     function currentEra() external view returns (uint32);
+//!     function activeEra() external view returns (uint32);
+//! }
+//! ```
+
+use super::{
+	abi::{self, error, EvmDataReader, EvmDataWriter, EvmResult},
+	dispatch::{self, Gasometer},
+	hash,
+};
+use evm::{executor::PrecompileOutput, Context};
+use frame_support::dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo};
+use pallet_evm::Precompile;
+use pallet_staking::RewardDestination;
+use sp_core::{H256, U256};
+use sp_runtime::traits::{Saturating, StaticLookup, Zero};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// `bond(bytes32,uint256,uint8)`
+pub const SELECTOR_BOND: u32 = 0xc46f0bdb;
+/// `bondExtra(uint256)`
+pub const SELECTOR_BOND_EXTRA: u32 = 0xeaca88de;
+/// `unbond(uint256)`
+pub const SELECTOR_UNBOND: u32 = 0x27de9e32;
+/// `withdrawUnbonded(uint32)`
+pub const SELECTOR_WITHDRAW_UNBONDED: u32 = 0x548a6706;
+/// `nominate(bytes32[])`
+pub const SELECTOR_NOMINATE: u32 = 0xf5330e96;
+/// `chill()`
+pub const SELECTOR_CHILL: u32 = 0x2b8a3ae6;
+/// `payoutStakers(bytes32,uint32)`
+pub const SELECTOR_PAYOUT_STAKERS: u32 = 0x6bc56089;
+/// `ledger(bytes32)`
+pub const SELECTOR_LEDGER: u32 = 0x15977d45;
+/// `bonded(bytes32)`
+pub const SELECTOR_BONDED: u32 = 0x51b0ce2d;
+/// `currentEra()`
+pub const SELECTOR_CURRENT_ERA: u32 = 0x973628f6;
+/// `activeEra()`
+pub const SELECTOR_ACTIVE_ERA: u32 = 0xc2f192cb;
+
+/// The staking precompile.
+pub struct Staking<R>(PhantomData<R>);
+
+impl<R> Precompile for Staking<R>
+where
+	R: pallet_staking::Config + pallet_evm::Config,
+	R::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + From<pallet_staking::Call<R>>,
+	<R::Call as Dispatchable>::Origin: From<Option<R::AccountId>>,
+{
+	fn execute(input: &[u8], target_gas: Option<u64>, context: &Context) -> EvmResult<PrecompileOutput> {
+		dispatch::ensure_direct_call(context, hash(2049))?;
+		let mut gasometer = Gasometer::new(target_gas);
+		let (selector, mut input) = EvmDataReader::new_with_selector(input)?;
+		let call = match selector {
+			SELECTOR_LEDGER => return Self::ledger(&mut gasometer, &mut input),
+			SELECTOR_BONDED => {
+				let stash: R::AccountId = input.read_decoded()?;
+				gasometer.record_reads::<R>(1)?;
+				let output = match pallet_staking::Pallet::<R>::bonded(&stash) {
+					Some(controller) => EvmDataWriter::new().write_encoded(&controller),
+					None => EvmDataWriter::new().write(H256::zero()),
+				};
+				return Ok(dispatch::succeed(&gasometer, output.build()));
+			}
+			SELECTOR_CURRENT_ERA => {
+				gasometer.record_reads::<R>(1)?;
+				let era = pallet_staking::Pallet::<R>::current_era().unwrap_or_default();
+				return Ok(dispatch::succeed(&gasometer, EvmDataWriter::new().write(era).build()));
+			}
+			SELECTOR_ACTIVE_ERA => {
+				gasometer.record_reads::<R>(1)?;
+				let era = pallet_staking::Pallet::<R>::active_era().map_or(0, |era| era.index);
+				return Ok(dispatch::succeed(&gasometer, EvmDataWriter::new().write(era).build()));
+			}
+			SELECTOR_BOND => {
+				let controller: R::AccountId = input.read_decoded()?;
+				let value = abi::to_balance(input.read()?)?;
+				let payee = match input.read::<u8>()? {
+					0 => RewardDestination::Staked,
+					1 => RewardDestination::Stash,
+					2 => RewardDestination::Controller,
+					_ => return Err(error("unknown reward destination")),
+				};
+				pallet_staking::Call::<R>::bond(R::Lookup::unlookup(controller), value, payee)
+			}
+			SELECTOR_BOND_EXTRA => pallet_staking::Call::<R>::bond_extra(abi::to_balance(input.read()?)?),
+			SELECTOR_UNBOND => pallet_staking::Call::<R>::unbond(abi::to_balance(input.read()?)?),
+			SELECTOR_WITHDRAW_UNBONDED => pallet_staking::Call::<R>::withdraw_unbonded(input.read()?),
+			SELECTOR_NOMINATE => {
+				let targets = input
+					.read::<Vec<H256>>()?
+					.into_iter()
+					.map(|target| {
+						EvmDataReader::new(target.as_bytes())
+							.read_decoded()
+							.map(R::Lookup::unlookup)
+					})
+					.collect::<EvmResult<Vec<_>>>()?;
+				pallet_staking::Call::<R>::nominate(targets)
+			}
+			SELECTOR_CHILL => pallet_staking::Call::<R>::chill(),
+			SELECTOR_PAYOUT_STAKERS => {
+				let validator_stash = input.read_decoded()?;
+				pallet_staking::Call::<R>::payout_stakers(validator_stash, input.read()?)
+			}
+			_ => return Err(error("unknown staking selector")),
+		};
+
+		dispatch::dispatch_as::<R>(&mut gasometer, context.caller, call)?;
+		Ok(dispatch::succeed(&gasometer, Vec::new()))
+	}
+}
+
+impl<R> Staking<R>
+where
+	R: pallet_staking::Config + pallet_evm::Config,
+{
+	fn ledger(gasometer: &mut Gasometer, input: &mut EvmDataReader) -> EvmResult<PrecompileOutput> {
+		let controller: R::AccountId = input.read_decoded()?;
+		gasometer.record_reads::<R>(1)?;
+		let output = match pallet_staking::Pallet::<R>::ledger(&controller) {
+			Some(ledger) => {
+				let unlocking = ledger
+					.unlocking
+					.iter()
+					.fold(Zero::zero(), |total: pallet_staking::BalanceOf<R>, chunk| {
+						total.saturating_add(chunk.value)
+					});
+				EvmDataWriter::new()
+					.write_encoded(&ledger.stash)
+					.write(abi::from_balance(ledger.total))
+					.write(abi::from_balance(ledger.active))
+					.write(abi::from_balance(unlocking))
+			}
+			None => EvmDataWriter::new()
+				.write(H256::zero())
+				.write(U256::zero())
+				.write(U256::zero())
+				.write(U256::zero()),
+		};
+		Ok(dispatch::succeed(gasometer, output.build()))
+	}
+}
@@ -0,0 +1,1642 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+	abi::{self, Bytes, EvmDataReader, EvmDataWriter, EvmResult},
+	assets_erc20::AssetsErc20,
+	dispatch::Gasometer,
+	erc20,
+	filtered_dispatch::FilteredDispatch,
+	governance::*,
+	hash,
+	identity::*,
+	mock::*,
+	native_erc20::NativeErc20,
+	proxy::*,
+	randomness::*,
+	staking,
+	wasm_bridge::*,
+	EdgewarePrecompiles, PrecompileSourceRunner, PRECOMPILE_CODE,
+};
+use crate::{migrations::PrecompileCode, Call as RuntimeCall, Origin as RuntimeOrigin, Runtime};
+use codec::Encode;
+use evm::{backend::Log, executor::PrecompileOutput, Context, ExitError};
+use frame_support::traits::{Currency, OnFinalize, OnInitialize, OnRuntimeUpgrade, Randomness as RandomnessT};
+use pallet_democracy::{VoteThreshold, Voting};
+use pallet_evm::{Precompile, PrecompileSet, Runner};
+use sp_core::{H160, H256, U256};
+use sp_io::hashing::keccak_256;
+use sp_runtime::{
+	traits::{Dispatchable, Hash},
+	DispatchError, MultiAddress,
+};
+
+/// The context of a contract at `0x42..42`, called by `caller`, that uses
+/// `DELEGATECALL` to run a precompile as itself.
+fn delegated(caller: H160) -> Context {
+	Context {
+		address: H160::repeat_byte(0x42),
+		caller,
+		apparent_value: U256::zero(),
+	}
+}
+
+fn native_erc20(caller: H160, input: Vec<u8>, target_gas: Option<u64>) -> EvmResult<PrecompileOutput> {
+	let context = Context {
+		address: hash(2048),
+		caller,
+		apparent_value: U256::zero(),
+	};
+	NativeErc20::<Test>::execute(&input, target_gas, &context)
+}
+
+/// An ERC-20 `Transfer` or `Approval` log, as given by its `event` signature.
+fn erc20_log(token: H160, event: &[u8], from: H160, to: H160, amount: u64) -> Log {
+	Log {
+		address: token,
+		topics: vec![H256(keccak_256(event)), from.into(), to.into()],
+		data: EvmDataWriter::new().write(U256::from(amount)).build(),
+	}
+}
+
+fn erc20_transfer(to: H160, amount: u64) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(erc20::SELECTOR_TRANSFER)
+		.write(to)
+		.write(U256::from(amount))
+		.build()
+}
+
+const ASSET: u32 = 7;
+
+/// Creates `ASSET`, owned by `ALICE`, names it and mints 1_000 of it to
+/// `ALICE`.
+fn create_asset() {
+	let owner = account(ALICE);
+	let create = pallet_assets::Call::force_create(ASSET, owner.clone(), true, 1);
+	Call::Assets(create).dispatch(Origin::root()).unwrap();
+	let metadata = pallet_assets::Call::set_metadata(ASSET, b"Test".to_vec(), b"TST".to_vec(), 12);
+	Call::Assets(metadata).dispatch(Origin::signed(owner.clone())).unwrap();
+	let mint = pallet_assets::Call::mint(ASSET, owner.clone(), 1_000);
+	Call::Assets(mint).dispatch(Origin::signed(owner)).unwrap();
+}
+
+fn asset_erc20(caller: H160, input: Vec<u8>, target_gas: Option<u64>) -> EvmResult<PrecompileOutput> {
+	let address = AssetsErc20::<Test>::address(ASSET);
+	let context = Context {
+		address,
+		caller,
+		apparent_value: U256::zero(),
+	};
+	AssetsErc20::<Test>::execute(address, &input, target_gas, &context).expect("an asset precompile address")
+}
+
+fn staking_precompile(caller: H160, input: Vec<u8>) -> EvmResult<PrecompileOutput> {
+	let context = Context {
+		address: hash(2049),
+		caller,
+		apparent_value: U256::zero(),
+	};
+	staking::Staking::<Test>::execute(&input, None, &context)
+}
+
+/// A `proxy` of `call` on behalf of `real`.
+fn proxy_call(real: H160, call: Call) -> Vec<u8> {
+	EvmDataWriter::new_with_selector(SELECTOR_PROXY)
+		.write_encoded(&account(real))
+		.write(Bytes(call.encode()))
+		.build()
+}
+
+/// An account as the `bytes32` the staking and proxy precompiles take.
+fn bytes32(account: &AccountId) -> H256 {
+	H256::from_slice(account.as_ref())
+}
+
+const TRANSFER_EVENT: &[u8] = b"Transfer(address,address,uint256)";
+const APPROVAL_EVENT: &[u8] = b"Approval(address,address,uint256)";
+
+/// The gas for a log with three topics and a word of data.
+const ERC20_LOG_COST: u64 = 375 + 3 * 375 + 8 * 32;
+
+fn governance(caller: H160, input: Vec<u8>) -> EvmResult<PrecompileOutput> {
+	governance_with_gas(caller, input, None)
+}
+
+fn governance_with_gas(caller: H160, input: Vec<u8>, target_gas: Option<u64>) -> EvmResult<PrecompileOutput> {
+	let context = Context {
+		address: hash(2050),
+		caller,
+		apparent_value: U256::zero(),
+	};
+	Governance::<Test>::execute(&input, target_gas, &context)
+}
+
+fn filtered_dispatch(caller: H160, call: Call) -> EvmResult<PrecompileOutput> {
+	let context = Context {
+		address: hash(1025),
+		caller,
+		apparent_value: U256::zero(),
+	};
+	FilteredDispatch::<Test>::execute(&call.encode(), None, &context)
+}
+
+fn transfer() -> Call {
+	Call::Balances(pallet_balances::Call::transfer(account(BOB), 10))
+}
+
+fn identity(caller: H160, input: Vec<u8>) -> EvmResult<PrecompileOutput> {
+	let context = Context {
+		address: hash(2051),
+		caller,
+		apparent_value: U256::zero(),
+	};
+	OnChainIdentity::<Test>::execute(&input, None, &context)
+}
+
+fn proxy_multisig(caller: H160, input: Vec<u8>) -> EvmResult<PrecompileOutput> {
+	let context = Context {
+		address: hash(2052),
+		caller,
+		apparent_value: U256::zero(),
+	};
+	ProxyMultisig::<Test>::execute(&input, None, &context)
+}
+
+/// Returns the randomness and block number the precompile gives for `subject`.
+fn random(subject: &[u8]) -> (H256, U256) {
+	let context = Context {
+		address: hash(2053),
+		caller: ALICE,
+		apparent_value: U256::zero(),
+	};
+	let input = EvmDataWriter::new_with_selector(SELECTOR_RANDOM)
+		.write(Bytes(subject.to_vec()))
+		.build();
+	let output = Randomness::<Test>::execute(&input, None, &context).unwrap();
+	let mut reader = EvmDataReader::new(&output.output);
+	(reader.read().unwrap(), reader.read().unwrap())
+}
+
+/// The gas limit of Wasm contract calls.
+const GAS_LIMIT: u64 = 10_000_000_000;
+
+/// A Wasm contract returning its input, reverting if the first byte is 1.
+const WASM_ECHO: &str = r#"
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+	(func (export "deploy"))
+	(func (export "call")
+		(i32.store (i32.const 0) (i32.const 1024))
+		(call $seal_input (i32.const 4) (i32.const 0))
+		(call $seal_return (i32.load8_u (i32.const 4)) (i32.const 4) (i32.load (i32.const 0)))
+	)
+)
+"#;
+
+/// A Wasm contract passing its input to the EVM chain extension, and returning
+/// the extension's status, as 4 little-endian bytes, and output.
+const WASM_EVM_CALLER: &str = r#"
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal0" "seal_call_chain_extension"
+		(func $seal_call_chain_extension (param i32 i32 i32 i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+	(func (export "deploy"))
+	(func (export "call")
+		(i32.store (i32.const 0) (i32.const 1020))
+		(call $seal_input (i32.const 4) (i32.const 0))
+		(i32.store (i32.const 1024) (i32.const 4096))
+		(i32.store
+			(i32.const 1028)
+			(call $seal_call_chain_extension
+				(i32.const 1) (i32.const 4) (i32.load (i32.const 0)) (i32.const 1032) (i32.const 1024)
+			)
+		)
+		(call $seal_return (i32.const 0) (i32.const 1028) (i32.add (i32.load (i32.const 1024)) (i32.const 4)))
+	)
+)
+"#;
+
+/// EVM code returning its call data: `CALLDATASIZE PUSH1 0 PUSH1 0
+/// CALLDATACOPY CALLDATASIZE PUSH1 0 RETURN`.
+const EVM_ECHO: [u8; 10] = [0x36, 0x60, 0x00, 0x60, 0x00, 0x37, 0x36, 0x60, 0x00, 0xf3];
+/// [`EVM_ECHO`], reverting with its call data.
+const EVM_REVERT: [u8; 10] = [0x36, 0x60, 0x00, 0x60, 0x00, 0x37, 0x36, 0x60, 0x00, 0xfd];
+
+/// Instantiates a Wasm contract from Alice and returns its account.
+fn deploy_wasm(wat: &str) -> AccountId {
+	let code = wat::parse_str(wat).unwrap();
+	let code_hash = <Test as frame_system::Config>::Hashing::hash(&code);
+	Contracts::instantiate_with_code(
+		Origin::signed(account(ALICE)),
+		100_000,
+		GAS_LIMIT,
+		code,
+		Vec::new(),
+		Vec::new(),
+	)
+	.unwrap();
+	Contracts::contract_address(&account(ALICE), &code_hash, &[])
+}
+
+fn wasm_bridge(caller: H160, dest: &AccountId, input: Vec<u8>, value: u128) -> EvmResult<PrecompileOutput> {
+	let context = Context {
+		address: hash(2054),
+		caller,
+		apparent_value: value.into(),
+	};
+	let input = EvmDataWriter::new_with_selector(SELECTOR_CALL)
+		.write_encoded(dest)
+		.write(Bytes(input))
+		.build();
+	WasmBridge::<Test>::execute(&input, Some(GAS_LIMIT), &context)
+}
+
+/// Calls `target` through the EVM chain extension from `contract`, a
+/// [`WASM_EVM_CALLER`], and returns the status and output.
+fn call_evm_from_wasm(contract: &AccountId, target: H160, input: Vec<u8>, gas_limit: u64) -> Option<(u32, Vec<u8>)> {
+	let call = EvmCall {
+		target,
+		input,
+		value: 0u128,
+		gas_limit,
+	};
+	let result = Contracts::bare_call(account(ALICE), contract.clone(), 0, GAS_LIMIT, call.encode(), false);
+	let data = result.result.ok()?.data;
+	let mut status = [0; 4];
+	status.copy_from_slice(&data[..4]);
+	Some((u32::from_le_bytes(status), data[4..].to_vec()))
+}
+
+/// Starts block `number`, as `pallet_randomness_collective_flip` sees it.
+fn start_block(number: BlockNumber) {
+	System::set_block_number(number);
+	System::set_parent_hash(H256::from_low_u64_be(number));
+	RandomnessCollectiveFlip::on_initialize(number);
+}
+
+/// Reads the `(hasIdentity, info, judgements)` an identity view returns.
+fn read_identity(output: PrecompileOutput) -> (bool, Info, Vec<RegistrarJudgement>) {
+	let mut reader = EvmDataReader::new(&output.output);
+	(reader.read().unwrap(), reader.read().unwrap(), reader.read().unwrap())
+}
+
+/// Splits a view's output into its return words.
+fn words(output: PrecompileOutput) -> Vec<U256> {
+	output.output.chunks(abi::WORD).map(U256::from_big_endian).collect()
+}
+
+/// A xorshift generator, so the fuzz tests are reproducible.
+struct Rng(u64);
+
+impl Rng {
+	fn next(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+
+	/// Random bytes, with words that are mostly small numbers so that offsets
+	/// and lengths often land inside the input.
+	fn input(&mut self) -> Vec<u8> {
+		let words = self.next() % 12;
+		let mut input = Vec::new();
+		for _ in 0..words {
+			let value = match self.next() % 4 {
+				0 => U256::from(self.next()) << (self.next() % 256) as usize,
+				1 => U256::max_value(),
+				_ => U256::from(self.next() % 400),
+			};
+			input.extend(EvmDataWriter::new().write(value).build());
+		}
+		input.truncate(input.len() - (self.next() % 33) as usize % (input.len() + 1));
+		input
+	}
+}
+
+/// Reads every supported type from `input`, which must not panic whatever the
+/// input is.
+fn read_everything(input: &[u8]) {
+	let mut reader = EvmDataReader::new(input);
+	let _ = reader.read::<H160>();
+	let _ = reader.read::<Bytes>();
+	let _ = reader.read::<Vec<H256>>();
+	let _ = reader.read::<Vec<Bytes>>();
+	let _ = reader.read::<Vec<Vec<u32>>>();
+	let _ = reader.read::<bool>();
+	let _ = reader.read::<u8>();
+	let _ = reader.read_decoded::<AccountId>();
+}
+
+#[test]
+fn abi_values_round_trip() {
+	let address = H160::repeat_byte(0x42);
+	let items = vec![H256::repeat_byte(1), H256::repeat_byte(2)];
+	let nested = vec![Bytes(b"edgeware".to_vec()), Bytes(vec![7u8; 40])];
+	let data = EvmDataWriter::new()
+		.write(address)
+		.write(U256::max_value())
+		.write(Bytes(b"hello".to_vec()))
+		.write(items.clone())
+		.write(true)
+		.write(nested.clone())
+		.write_encoded(&account(ALICE))
+		.build();
+
+	let mut reader = EvmDataReader::new(&data);
+	assert_eq!(reader.read::<H160>().unwrap(), address);
+	assert_eq!(reader.read::<U256>().unwrap(), U256::max_value());
+	assert_eq!(reader.read::<Bytes>().unwrap(), Bytes(b"hello".to_vec()));
+	assert_eq!(reader.read::<Vec<H256>>().unwrap(), items);
+	assert!(reader.read::<bool>().unwrap());
+	assert_eq!(reader.read::<Vec<Bytes>>().unwrap(), nested);
+	assert_eq!(reader.read_decoded::<AccountId>().unwrap(), account(ALICE));
+}
+
+#[test]
+fn abi_encoding_matches_solidity() {
+	// `abi.encode(uint32(5), "hi")`
+	let data = EvmDataWriter::new().write(5u32).write(Bytes(b"hi".to_vec())).build();
+	let mut expected = vec![0u8; 4 * abi::WORD];
+	expected[31] = 5;
+	expected[63] = 0x40;
+	expected[95] = 2;
+	expected[96..98].copy_from_slice(b"hi");
+	assert_eq!(data, expected);
+
+	// `revert("hi")`
+	let mut reason = vec![0x08, 0xc3, 0x79, 0xa0];
+	reason.extend(EvmDataWriter::new().write(U256::from(abi::WORD)).build());
+	reason.extend(&expected[2 * abi::WORD..]);
+	assert_eq!(abi::revert_reason(b"hi"), reason);
+}
+
+#[test]
+fn abi_rejects_malformed_values() {
+	let word = |value: U256| EvmDataWriter::new().write(value).build();
+
+	let mut dirty_address = word(U256::zero());
+	dirty_address[0] = 1;
+	assert!(EvmDataReader::new(&dirty_address).read::<H160>().is_err());
+	assert!(EvmDataReader::new(&word(2.into())).read::<bool>().is_err());
+	assert!(EvmDataReader::new(&word(256.into())).read::<u8>().is_err());
+	assert!(EvmDataReader::new(&word(65536.into())).read::<u16>().is_err());
+	assert!(EvmDataReader::new(&word(U256::from(u32::max_value()) + 1))
+		.read::<u32>()
+		.is_err());
+	assert!(EvmDataReader::new(&word(U256::from(u64::max_value()) + 1))
+		.read::<u64>()
+		.is_err());
+	assert!(EvmDataReader::new(&[0u8; 31]).read::<U256>().is_err());
+	assert!(EvmDataReader::new_with_selector(&[0u8; 3]).is_err());
+
+	// An offset past the end of the input.
+	assert!(EvmDataReader::new(&word(64.into())).read::<Bytes>().is_err());
+	// A length past the end of the input.
+	let mut long_bytes = word(32.into());
+	long_bytes.extend(word(33.into()));
+	long_bytes.extend(vec![0u8; 32]);
+	assert!(EvmDataReader::new(&long_bytes).read::<Bytes>().is_err());
+	// A huge array length fails before anything is allocated.
+	let mut huge_array = word(32.into());
+	huge_array.extend(word(u32::max_value().into()));
+	assert!(EvmDataReader::new(&huge_array).read::<Vec<U256>>().is_err());
+}
+
+#[test]
+fn abi_rejects_every_truncation() {
+	let data = EvmDataWriter::new()
+		.write(H160::repeat_byte(0x42))
+		.write(Bytes(b"hello".to_vec()))
+		.write(vec![H256::repeat_byte(1), H256::repeat_byte(2)])
+		.build();
+	for len in 0..data.len() {
+		let mut reader = EvmDataReader::new(&data[..len]);
+		let result = (|| -> EvmResult {
+			reader.read::<H160>()?;
+			reader.read::<Bytes>()?;
+			reader.read::<Vec<H256>>()?;
+			Ok(())
+		})();
+		assert!(result.is_err(), "truncated to {} bytes", len);
+	}
+}
+
+#[test]
+fn abi_fuzz_does_not_panic() {
+	let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+	for _ in 0..10_000 {
+		read_everything(&rng.input());
+	}
+}
+
+#[test]
+fn precompile_fuzz_does_not_panic() {
+	new_test_ext().execute_with(|| {
+		let selectors = [
+			SELECTOR_PROPOSE,
+			SELECTOR_STANDARD_VOTE,
+			SELECTOR_DELEGATE,
+			SELECTOR_VOTE_CANDIDATES,
+			SELECTOR_PUBLIC_PROP,
+			SELECTOR_REFERENDUM_INFO,
+			SELECTOR_REFERENDUM_TALLY,
+		];
+		let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+		for _ in 0..1_000 {
+			let selector = selectors[rng.next() as usize % selectors.len()];
+			let mut input = selector.to_be_bytes().to_vec();
+			input.extend(rng.input());
+			let _ = governance(ALICE, input);
+		}
+	});
+}
+
+#[test]
+fn gasometer_charges_storage_reads() {
+	new_test_ext().execute_with(|| {
+		let count = || EvmDataWriter::new_with_selector(SELECTOR_PUBLIC_PROP_COUNT).build();
+		// The mock charges 10 gas per read.
+		assert_eq!(governance_with_gas(ALICE, count(), Some(10)).unwrap().cost, 10);
+		assert_eq!(
+			governance_with_gas(ALICE, count(), Some(9)).unwrap_err(),
+			ExitError::OutOfGas
+		);
+
+		let mut gasometer = Gasometer::new(Some(25));
+		gasometer.record_reads::<Test>(2).unwrap();
+		assert_eq!(gasometer.remaining(), Some(5));
+		assert_eq!(gasometer.record_reads::<Test>(1), Err(ExitError::OutOfGas));
+		assert_eq!(gasometer.used(), 20);
+	});
+}
+
+#[test]
+fn native_erc20_transfer() {
+	new_test_ext().execute_with(|| {
+		// Two reads and two writes for the balances, and the log.
+		let cost = 2 * 10 + 2 * 100 + ERC20_LOG_COST;
+		assert_eq!(
+			native_erc20(ALICE, erc20_transfer(BOB, 100), Some(cost - 1)).unwrap_err(),
+			ExitError::OutOfGas
+		);
+		assert_eq!(Balances::free_balance(account(ALICE)), 1_000_000);
+
+		let output = native_erc20(ALICE, erc20_transfer(BOB, 100), Some(cost)).unwrap();
+		assert_eq!(output.cost, cost);
+		assert_eq!(output.output, EvmDataWriter::new().write(true).build());
+		let log = erc20_log(hash(2048), TRANSFER_EVENT, ALICE, BOB, 100);
+		assert_eq!(output.logs, vec![log]);
+		assert_eq!(Balances::free_balance(account(ALICE)), 999_900);
+		assert_eq!(Balances::free_balance(account(BOB)), 1_000_100);
+
+		assert_eq!(
+			native_erc20(ALICE, erc20_transfer(BOB, 2_000_000), None).unwrap_err(),
+			abi::error("transfer failed")
+		);
+	});
+}
+
+#[test]
+fn native_erc20_approve_and_transfer_from() {
+	new_test_ext().execute_with(|| {
+		let approve = EvmDataWriter::new_with_selector(erc20::SELECTOR_APPROVE)
+			.write(BOB)
+			.write(U256::from(100))
+			.build();
+		let output = native_erc20(ALICE, approve, None).unwrap();
+		// One write for the allowance, and the log.
+		assert_eq!(output.cost, 100 + ERC20_LOG_COST);
+		assert_eq!(output.output, EvmDataWriter::new().write(true).build());
+		let log = erc20_log(hash(2048), APPROVAL_EVENT, ALICE, BOB, 100);
+		assert_eq!(output.logs, vec![log]);
+
+		let allowance = || {
+			let input = EvmDataWriter::new_with_selector(erc20::SELECTOR_ALLOWANCE)
+				.write(ALICE)
+				.write(BOB)
+				.build();
+			native_erc20(BOB, input, None).unwrap().output
+		};
+		assert_eq!(allowance(), EvmDataWriter::new().write(U256::from(100)).build());
+
+		let transfer_from = |amount: u64| {
+			EvmDataWriter::new_with_selector(erc20::SELECTOR_TRANSFER_FROM)
+				.write(ALICE)
+				.write(BOB)
+				.write(U256::from(amount))
+				.build()
+		};
+		assert_eq!(
+			native_erc20(BOB, transfer_from(101), None).unwrap_err(),
+			abi::error("insufficient allowance")
+		);
+
+		let output = native_erc20(BOB, transfer_from(60), None).unwrap();
+		// The transfer, a read and a write for the allowance, and the log.
+		assert_eq!(output.cost, 2 * 10 + 2 * 100 + 10 + 100 + ERC20_LOG_COST);
+		let log = erc20_log(hash(2048), TRANSFER_EVENT, ALICE, BOB, 60);
+		assert_eq!(output.logs, vec![log]);
+		assert_eq!(Balances::free_balance(account(ALICE)), 999_940);
+		assert_eq!(Balances::free_balance(account(BOB)), 1_000_060);
+		assert_eq!(allowance(), EvmDataWriter::new().write(U256::from(40)).build());
+	});
+}
+
+#[test]
+fn native_erc20_rejects_delegate_calls() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			NativeErc20::<Test>::execute(&erc20_transfer(BOB, 100), None, &delegated(ALICE)).unwrap_err(),
+			abi::error("cannot be called with DELEGATECALL or CALLCODE")
+		);
+		assert_eq!(Balances::free_balance(account(ALICE)), 1_000_000);
+	});
+}
+
+#[test]
+fn asset_precompile_addresses_round_trip() {
+	let mut bytes = [0u8; 20];
+	bytes[..4].copy_from_slice(&[0xff; 4]);
+	bytes[16..].copy_from_slice(&[1, 2, 3, 4]);
+	assert_eq!(AssetsErc20::<Test>::address(0x0102_0304), H160(bytes));
+	assert_eq!(AssetsErc20::<Test>::asset(H160(bytes)), Some(0x0102_0304));
+
+	assert_eq!(AssetsErc20::<Test>::asset(hash(2048)), None);
+	bytes[8] = 1;
+	assert_eq!(AssetsErc20::<Test>::asset(H160(bytes)), None);
+}
+
+#[test]
+fn asset_addresses_are_precompiles_without_code() {
+	new_test_ext().execute_with(|| {
+		let address = AssetsErc20::<Test>::address(ASSET);
+		create_asset();
+		assert!(EVM::account_codes(address).is_empty());
+		assert!(EdgewarePrecompiles::<Test>::is_precompile(address));
+		let output = asset_erc20(
+			ALICE,
+			EvmDataWriter::new_with_selector(erc20::SELECTOR_DECIMALS).build(),
+			None,
+		);
+		assert_eq!(output.unwrap().output, EvmDataWriter::new().write(12u8).build());
+	});
+}
+
+#[test]
+fn asset_erc20_metadata_and_balances() {
+	new_test_ext().execute_with(|| {
+		create_asset();
+		let view = |input| {
+			let output = asset_erc20(BOB, input, None).unwrap();
+			assert_eq!(output.cost, 10);
+			output.output
+		};
+		let selector = |selector| EvmDataWriter::new_with_selector(selector).build();
+
+		assert_eq!(
+			view(selector(erc20::SELECTOR_NAME)),
+			EvmDataWriter::new().write(Bytes(b"Test".to_vec())).build()
+		);
+		assert_eq!(
+			view(selector(erc20::SELECTOR_SYMBOL)),
+			EvmDataWriter::new().write(Bytes(b"TST".to_vec())).build()
+		);
+		assert_eq!(
+			view(selector(erc20::SELECTOR_DECIMALS)),
+			EvmDataWriter::new().write(12u8).build()
+		);
+		assert_eq!(
+			view(selector(erc20::SELECTOR_TOTAL_SUPPLY)),
+			EvmDataWriter::new().write(U256::from(1_000)).build()
+		);
+		let balance_of = |who: H160| {
+			EvmDataWriter::new_with_selector(erc20::SELECTOR_BALANCE_OF)
+				.write(who)
+				.build()
+		};
+		assert_eq!(
+			view(balance_of(ALICE)),
+			EvmDataWriter::new().write(U256::from(1_000)).build()
+		);
+		assert_eq!(view(balance_of(BOB)), EvmDataWriter::new().write(U256::zero()).build());
+	});
+}
+
+#[test]
+fn asset_erc20_transfer() {
+	new_test_ext().execute_with(|| {
+		create_asset();
+		// Three reads and three writes for the asset and its accounts, and the log.
+		let cost = 3 * 10 + 3 * 100 + ERC20_LOG_COST;
+		assert_eq!(
+			asset_erc20(ALICE, erc20_transfer(BOB, 100), Some(cost - 1)).unwrap_err(),
+			ExitError::OutOfGas
+		);
+		assert_eq!(Assets::balance(ASSET, account(ALICE)), 1_000);
+
+		let output = asset_erc20(ALICE, erc20_transfer(BOB, 100), Some(cost)).unwrap();
+		assert_eq!(output.cost, cost);
+		assert_eq!(output.output, EvmDataWriter::new().write(true).build());
+		let log = erc20_log(AssetsErc20::<Test>::address(ASSET), TRANSFER_EVENT, ALICE, BOB, 100);
+		assert_eq!(output.logs, vec![log]);
+		assert_eq!(Assets::balance(ASSET, account(ALICE)), 900);
+		assert_eq!(Assets::balance(ASSET, account(BOB)), 100);
+
+		assert_eq!(
+			asset_erc20(ALICE, erc20_transfer(BOB, 1_000), None).unwrap_err(),
+			abi::error("transfer failed")
+		);
+	});
+}
+
+#[test]
+fn asset_erc20_approve_and_transfer_from() {
+	new_test_ext().execute_with(|| {
+		create_asset();
+		let approve = EvmDataWriter::new_with_selector(erc20::SELECTOR_APPROVE)
+			.write(BOB)
+			.write(U256::from(100))
+			.build();
+		let output = asset_erc20(ALICE, approve, None).unwrap();
+		assert_eq!(output.cost, 100 + ERC20_LOG_COST);
+		let log = erc20_log(AssetsErc20::<Test>::address(ASSET), APPROVAL_EVENT, ALICE, BOB, 100);
+		assert_eq!(output.logs, vec![log]);
+
+		let transfer_from = EvmDataWriter::new_with_selector(erc20::SELECTOR_TRANSFER_FROM)
+			.write(ALICE)
+			.write(BOB)
+			.write(U256::from(60))
+			.build();
+		let output = asset_erc20(BOB, transfer_from, None).unwrap();
+		// The transfer, a read and a write for the allowance, and the log.
+		assert_eq!(output.cost, 3 * 10 + 3 * 100 + 10 + 100 + ERC20_LOG_COST);
+		let log = erc20_log(AssetsErc20::<Test>::address(ASSET), TRANSFER_EVENT, ALICE, BOB, 60);
+		assert_eq!(output.logs, vec![log]);
+		assert_eq!(Assets::balance(ASSET, account(ALICE)), 940);
+		assert_eq!(Assets::balance(ASSET, account(BOB)), 60);
+		let allowance = evm_allowances::Pallet::<Test>::allowance(AssetsErc20::<Test>::address(ASSET), ALICE, BOB);
+		assert_eq!(allowance, U256::from(40));
+	});
+}
+
+#[test]
+fn asset_erc20_rejects_missing_assets_and_delegate_calls() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			asset_erc20(ALICE, erc20_transfer(BOB, 100), None).unwrap_err(),
+			abi::error("asset does not exist")
+		);
+
+		create_asset();
+		let address = AssetsErc20::<Test>::address(ASSET);
+		let output = AssetsErc20::<Test>::execute(address, &erc20_transfer(BOB, 100), None, &delegated(ALICE));
+		assert_eq!(
+			output.unwrap().unwrap_err(),
+			abi::error("cannot be called with DELEGATECALL or CALLCODE")
+		);
+		assert_eq!(Assets::balance(ASSET, account(ALICE)), 1_000);
+	});
+}
+
+#[test]
+fn runtime_serves_assets_created_through_pallet_assets() {
+	sp_io::TestExternalities::default().execute_with(|| {
+		let force_create = pallet_assets::Call::force_create(ASSET, MultiAddress::Id(account(ALICE)), true, 1);
+
+		let address = AssetsErc20::<Runtime>::address(ASSET);
+		let context = Context {
+			address,
+			caller: ALICE,
+			apparent_value: U256::zero(),
+		};
+		let decimals = EvmDataWriter::new_with_selector(erc20::SELECTOR_DECIMALS).build();
+		let execute = || EdgewarePrecompiles::<Runtime>::execute(address, &decimals, None, &context).unwrap();
+		assert_eq!(execute().unwrap_err(), abi::error("asset does not exist"));
+
+		RuntimeCall::Assets(force_create)
+			.dispatch(RuntimeOrigin::root())
+			.unwrap();
+		assert!(crate::EVM::account_codes(address).is_empty());
+		assert_eq!(execute().unwrap().output, EvmDataWriter::new().write(0u8).build());
+	});
+}
+
+#[test]
+fn bond_unbond_and_read_the_ledger() {
+	new_test_ext().execute_with(|| {
+		let stash = account(ALICE);
+		let bond = EvmDataWriter::new_with_selector(staking::SELECTOR_BOND)
+			.write(bytes32(&stash))
+			.write(U256::from(1_000))
+			.write(1u8)
+			.build();
+		assert!(staking_precompile(ALICE, bond).unwrap().output.is_empty());
+		assert_eq!(Staking::bonded(&stash), Some(stash.clone()));
+		assert_eq!(Staking::payee(&stash), pallet_staking::RewardDestination::Stash);
+
+		let unbond = EvmDataWriter::new_with_selector(staking::SELECTOR_UNBOND)
+			.write(U256::from(400))
+			.build();
+		staking_precompile(ALICE, unbond).unwrap();
+
+		let ledger = |controller: &AccountId| {
+			let input = EvmDataWriter::new_with_selector(staking::SELECTOR_LEDGER)
+				.write(bytes32(controller))
+				.build();
+			let output = staking_precompile(BOB, input).unwrap();
+			assert_eq!(output.cost, 10);
+			output.output
+		};
+		let expected = EvmDataWriter::new()
+			.write(bytes32(&stash))
+			.write(U256::from(1_000))
+			.write(U256::from(600))
+			.write(U256::from(400))
+			.build();
+		assert_eq!(ledger(&stash), expected);
+		assert_eq!(ledger(&account(BOB)), vec![0; 4 * 32]);
+
+		let bonded = |stash: &AccountId| {
+			let input = EvmDataWriter::new_with_selector(staking::SELECTOR_BONDED)
+				.write(bytes32(stash))
+				.build();
+			staking_precompile(BOB, input).unwrap().output
+		};
+		assert_eq!(bonded(&stash), EvmDataWriter::new().write(bytes32(&stash)).build());
+		assert_eq!(bonded(&account(BOB)), EvmDataWriter::new().write(H256::zero()).build());
+	});
+}
+
+#[test]
+fn nominate_and_chill() {
+	new_test_ext().execute_with(|| {
+		let stash = account(ALICE);
+		let bond = EvmDataWriter::new_with_selector(staking::SELECTOR_BOND)
+			.write(bytes32(&stash))
+			.write(U256::from(1_000))
+			.write(0u8)
+			.build();
+		staking_precompile(ALICE, bond).unwrap();
+
+		let nominate = EvmDataWriter::new_with_selector(staking::SELECTOR_NOMINATE)
+			.write(vec![bytes32(&validator())])
+			.build();
+		staking_precompile(ALICE, nominate).unwrap();
+		assert_eq!(Staking::nominators(&stash).unwrap().targets, vec![validator()]);
+
+		let chill = EvmDataWriter::new_with_selector(staking::SELECTOR_CHILL).build();
+		staking_precompile(ALICE, chill).unwrap();
+		assert!(Staking::nominators(&stash).is_none());
+	});
+}
+
+#[test]
+fn payout_stakers_after_the_era() {
+	new_test_ext().execute_with(|| {
+		let era = |selector| {
+			let output = staking_precompile(BOB, EvmDataWriter::new_with_selector(selector).build());
+			output.unwrap().output
+		};
+		assert_eq!(
+			era(staking::SELECTOR_CURRENT_ERA),
+			EvmDataWriter::new().write(0u32).build()
+		);
+		assert_eq!(
+			era(staking::SELECTOR_ACTIVE_ERA),
+			EvmDataWriter::new().write(0u32).build()
+		);
+
+		// Give the validator all the points of era 0, start the era's clock and
+		// end it.
+		Staking::reward_by_ids(vec![(validator(), 1)]);
+		Staking::on_finalize(System::block_number());
+		Session::rotate_session();
+		Session::rotate_session();
+		let active_era = Staking::active_era().unwrap().index;
+		assert!(active_era > 0);
+		assert_eq!(
+			era(staking::SELECTOR_ACTIVE_ERA),
+			EvmDataWriter::new().write(active_era).build()
+		);
+		let current_era = Staking::current_era().unwrap();
+		assert_eq!(
+			era(staking::SELECTOR_CURRENT_ERA),
+			EvmDataWriter::new().write(current_era).build()
+		);
+
+		let payout = EvmDataWriter::new_with_selector(staking::SELECTOR_PAYOUT_STAKERS)
+			.write(bytes32(&validator()))
+			.write(0u32)
+			.build();
+		staking_precompile(BOB, payout.clone()).unwrap();
+		// The validator stakes its rewards.
+		let ledger = Staking::ledger(&validator()).unwrap();
+		assert_eq!((ledger.total, ledger.active), (2_000, 2_000));
+
+		assert_eq!(
+			staking_precompile(BOB, payout).unwrap_err(),
+			abi::error("AlreadyClaimed")
+		);
+	});
+}
+
+#[test]
+fn staking_rejects_delegate_calls() {
+	new_test_ext().execute_with(|| {
+		let stash = account(ALICE);
+		let bond = EvmDataWriter::new_with_selector(staking::SELECTOR_BOND)
+			.write(bytes32(&stash))
+			.write(U256::from(1_000))
+			.write(0u8)
+			.build();
+		assert_eq!(
+			staking::Staking::<Test>::execute(&bond, None, &delegated(ALICE)).unwrap_err(),
+			abi::error("cannot be called with DELEGATECALL or CALLCODE")
+		);
+		assert_eq!(Staking::bonded(&stash), None);
+	});
+}
+
+#[test]
+fn propose_and_second_through_calldata() {
+	new_test_ext().execute_with(|| {
+		let proposal_hash = H256::repeat_byte(0x11);
+		let propose = EvmDataWriter::new_with_selector(SELECTOR_PROPOSE)
+			.write(proposal_hash)
+			.write(U256::from(100))
+			.build();
+		governance(ALICE, propose).unwrap();
+		let second = EvmDataWriter::new_with_selector(SELECTOR_SECOND)
+			.write(0u32)
+			.write(1u32)
+			.build();
+		governance(BOB, second).unwrap();
+
+		let count = governance(
+			BOB,
+			EvmDataWriter::new_with_selector(SELECTOR_PUBLIC_PROP_COUNT).build(),
+		)
+		.unwrap();
+		assert_eq!(words(count), vec![U256::one()]);
+
+		let prop = governance(
+			BOB,
+			EvmDataWriter::new_with_selector(SELECTOR_PUBLIC_PROP)
+				.write(0u32)
+				.build(),
+		)
+		.unwrap();
+		let expected = EvmDataWriter::new()
+			.write(0u32)
+			.write(proposal_hash)
+			.write_encoded(&account(ALICE))
+			.build();
+		assert_eq!(prop.output, expected);
+
+		let deposit = governance(
+			BOB,
+			EvmDataWriter::new_with_selector(SELECTOR_DEPOSIT_OF)
+				.write(0u32)
+				.build(),
+		)
+		.unwrap();
+		assert_eq!(words(deposit), vec![U256::from(100), U256::from(2)]);
+		assert_eq!(Balances::reserved_balance(account(BOB)), 100);
+	});
+}
+
+#[test]
+fn standard_vote_updates_the_tally() {
+	new_test_ext().execute_with(|| {
+		let proposal_hash = H256::repeat_byte(0x22);
+		let index = Democracy::internal_start_referendum(proposal_hash, VoteThreshold::SimpleMajority, 2);
+		let vote = |aye: bool, value: u64, conviction: u8| {
+			EvmDataWriter::new_with_selector(SELECTOR_STANDARD_VOTE)
+				.write(index)
+				.write(aye)
+				.write(U256::from(value))
+				.write(conviction)
+				.build()
+		};
+		let tally = || {
+			EvmDataWriter::new_with_selector(SELECTOR_REFERENDUM_TALLY)
+				.write(index)
+				.build()
+		};
+
+		// An aye with `Locked2x` conviction counts twice.
+		governance(ALICE, vote(true, 1_000, 2)).unwrap();
+		governance(BOB, vote(false, 500, 1)).unwrap();
+		assert_eq!(words(governance(ALICE, tally()).unwrap()), vec![
+			U256::from(2_000),
+			U256::from(500),
+			U256::from(1_500)
+		]);
+
+		let info = governance(
+			ALICE,
+			EvmDataWriter::new_with_selector(SELECTOR_REFERENDUM_INFO)
+				.write(index)
+				.build(),
+		)
+		.unwrap();
+		let expected = EvmDataWriter::new()
+			.write(1u8)
+			.write(11u32)
+			.write(proposal_hash)
+			.write(2u8)
+			.write(2u32)
+			.build();
+		assert_eq!(info.output, expected);
+
+		let remove_vote = EvmDataWriter::new_with_selector(SELECTOR_REMOVE_VOTE)
+			.write(index)
+			.build();
+		governance(BOB, remove_vote).unwrap();
+		assert_eq!(words(governance(ALICE, tally()).unwrap()), vec![
+			U256::from(2_000),
+			U256::zero(),
+			U256::from(1_000)
+		]);
+	});
+}
+
+#[test]
+fn delegate_and_undelegate() {
+	new_test_ext().execute_with(|| {
+		let delegate = EvmDataWriter::new_with_selector(SELECTOR_DELEGATE)
+			.write_encoded(&account(BOB))
+			.write(1u8)
+			.write(U256::from(300))
+			.build();
+		governance(ALICE, delegate).unwrap();
+		assert!(matches!(
+			Democracy::voting_of(account(ALICE)),
+			Voting::Delegating { balance: 300, ref target, .. } if *target == account(BOB)
+		));
+
+		governance(ALICE, EvmDataWriter::new_with_selector(SELECTOR_UNDELEGATE).build()).unwrap();
+		assert!(matches!(Democracy::voting_of(account(ALICE)), Voting::Direct { .. }));
+	});
+}
+
+#[test]
+fn vote_for_council_candidates() {
+	new_test_ext().execute_with(|| {
+		PhragmenElection::submit_candidacy(Origin::signed(account(BOB)), 0).unwrap();
+
+		let bob = EvmDataWriter::new().write_encoded(&account(BOB)).build();
+		let vote = EvmDataWriter::new_with_selector(SELECTOR_VOTE_CANDIDATES)
+			.write(vec![H256::from_slice(&bob)])
+			.write(U256::from(400))
+			.build();
+		governance(ALICE, vote).unwrap();
+		let voter = pallet_elections_phragmen::Voting::<Test>::get(account(ALICE));
+		assert_eq!(voter.votes, vec![account(BOB)]);
+		assert_eq!(voter.stake, 400);
+
+		governance(ALICE, EvmDataWriter::new_with_selector(SELECTOR_REMOVE_VOTER).build()).unwrap();
+		assert!(!pallet_elections_phragmen::Voting::<Test>::contains_key(account(ALICE)));
+	});
+}
+
+#[test]
+fn rejects_malformed_calldata() {
+	new_test_ext().execute_with(|| {
+		let index = Democracy::internal_start_referendum(H256::zero(), VoteThreshold::SimpleMajority, 0);
+		let vote = |aye: u64, conviction: u64| {
+			EvmDataWriter::new_with_selector(SELECTOR_STANDARD_VOTE)
+				.write(index)
+				.write(U256::from(aye))
+				.write(U256::from(10))
+				.write(U256::from(conviction))
+				.build()
+		};
+
+		assert!(governance(ALICE, vote(2, 0)).is_err());
+		assert!(governance(ALICE, vote(1, 7)).is_err());
+		assert!(governance(ALICE, vote(1, 0)[..100].to_vec()).is_err());
+		assert!(governance(ALICE, vec![0x12, 0x34]).is_err());
+		assert!(governance(ALICE, EvmDataWriter::new_with_selector(0xdeadbeef).build()).is_err());
+		let tally = EvmDataWriter::new_with_selector(SELECTOR_REFERENDUM_TALLY)
+			.write(index + 1)
+			.build();
+		assert!(governance(ALICE, tally).is_err());
+		let prop = EvmDataWriter::new_with_selector(SELECTOR_PUBLIC_PROP)
+			.write(0u32)
+			.build();
+		assert!(governance(ALICE, prop).is_err());
+	});
+}
+
+#[test]
+fn governance_rejects_delegate_calls() {
+	new_test_ext().execute_with(|| {
+		let propose = EvmDataWriter::new_with_selector(SELECTOR_PROPOSE)
+			.write(H256::repeat_byte(0x11))
+			.write(U256::from(100))
+			.build();
+		assert_eq!(
+			Governance::<Test>::execute(&propose, None, &delegated(ALICE)).unwrap_err(),
+			abi::error("cannot be called with DELEGATECALL or CALLCODE")
+		);
+		assert_eq!(Democracy::public_prop_count(), 0);
+	});
+}
+
+#[test]
+fn dispatches_allowed_calls() {
+	new_test_ext().execute_with(|| {
+		let remark = Call::System(frame_system::Call::remark(b"hello".to_vec()));
+		let output = filtered_dispatch(ALICE, remark).unwrap();
+		assert!(output.output.is_empty());
+		assert!(output.logs.is_empty());
+
+		let propose = Call::Democracy(pallet_democracy::Call::propose(H256::repeat_byte(1), 100));
+		assert!(filtered_dispatch(ALICE, propose).unwrap().output.is_empty());
+		assert_eq!(Democracy::public_prop_count(), 1);
+	});
+}
+
+#[test]
+fn rejects_disallowed_calls() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			filtered_dispatch(ALICE, transfer()).unwrap_err(),
+			abi::error("call Balances.transfer is not allowed")
+		);
+		assert_eq!(Balances::free_balance(account(BOB)), 1_000_000);
+		assert!(System::events().iter().any(|record| record.event
+			== Event::evm_dispatch(evm_dispatch::Event::CallRejected(
+				account(ALICE),
+				b"Balances".to_vec(),
+				b"transfer".to_vec(),
+			))));
+	});
+}
+
+#[test]
+fn rejects_disallowed_calls_inside_batches() {
+	new_test_ext().execute_with(|| {
+		let remark = Call::System(frame_system::Call::remark(vec![]));
+		let inner = Call::Utility(pallet_utility::Call::batch(vec![remark.clone(), transfer()]));
+		let batch = Call::Utility(pallet_utility::Call::batch_all(vec![remark, inner]));
+
+		assert_eq!(
+			filtered_dispatch(ALICE, batch).unwrap_err(),
+			abi::error("call Balances.transfer is not allowed")
+		);
+		assert_eq!(Balances::free_balance(account(BOB)), 1_000_000);
+
+		let derivative = Call::Utility(pallet_utility::Call::as_derivative(0, Box::new(transfer())));
+		assert!(filtered_dispatch(ALICE, derivative).is_err());
+	});
+}
+
+#[test]
+fn filtered_dispatch_rejects_delegate_calls() {
+	new_test_ext().execute_with(|| {
+		let remark = Call::System(frame_system::Call::remark(b"hello".to_vec()));
+		assert_eq!(
+			FilteredDispatch::<Test>::execute(&remark.encode(), None, &delegated(ALICE)).unwrap_err(),
+			abi::error("cannot be called with DELEGATECALL or CALLCODE")
+		);
+	});
+}
+
+#[test]
+fn rejects_undecodable_calls() {
+	new_test_ext().execute_with(|| {
+		let context = Context {
+			address: hash(1025),
+			caller: ALICE,
+			apparent_value: U256::zero(),
+		};
+		assert!(FilteredDispatch::<Test>::execute(&[0xff, 0xff, 0xff], None, &context).is_err());
+	});
+}
+
+#[test]
+fn used_addresses_match_the_precompile_set() {
+	sp_io::TestExternalities::default().execute_with(|| {
+		let used: Vec<H160> = EdgewarePrecompiles::<Runtime>::used_addresses().collect();
+		for address in (0..4096).map(hash) {
+			let context = Context {
+				address,
+				caller: ALICE,
+				apparent_value: U256::zero(),
+			};
+			let executed = EdgewarePrecompiles::<Runtime>::execute(address, &[], None, &context).is_some();
+			assert_eq!(executed, used.contains(&address), "{:?}", address);
+			assert_eq!(EdgewarePrecompiles::<Runtime>::is_precompile(address), executed);
+		}
+		assert!(EdgewarePrecompiles::<Runtime>::is_precompile(
+			AssetsErc20::<Runtime>::address(7)
+		));
+	});
+}
+
+#[test]
+fn genesis_and_migration_store_precompile_code() {
+	let genesis = EdgewarePrecompiles::<Runtime>::genesis_accounts();
+	assert_eq!(genesis.len(), EdgewarePrecompiles::<Runtime>::used_addresses().count());
+	for address in EdgewarePrecompiles::<Runtime>::used_addresses() {
+		assert_eq!(genesis[&address].code, PRECOMPILE_CODE.to_vec());
+	}
+
+	sp_io::TestExternalities::default().execute_with(|| {
+		let weight = PrecompileCode::on_runtime_upgrade();
+		for address in EdgewarePrecompiles::<Runtime>::used_addresses() {
+			assert_eq!(crate::EVM::account_codes(address), PRECOMPILE_CODE.to_vec());
+		}
+		// The code is already there, so the second run only reads.
+		assert!(PrecompileCode::on_runtime_upgrade() < weight);
+	});
+}
+
+#[test]
+fn rejects_transactions_from_precompile_addresses() {
+	sp_io::TestExternalities::default().execute_with(|| {
+		let config = <Runtime as pallet_evm::Config>::config();
+		let call = |source| {
+			PrecompileSourceRunner::<Runtime>::call(source, BOB, vec![], U256::zero(), 100_000, None, None, config)
+		};
+		let rejected = Err(DispatchError::Other("transaction sent from a precompile address"));
+
+		let assets = AssetsErc20::<Runtime>::address(7);
+		for source in EdgewarePrecompiles::<Runtime>::used_addresses().chain(Some(assets)) {
+			assert_eq!(call(source).map(|_| ()), rejected);
+		}
+		let create =
+			PrecompileSourceRunner::<Runtime>::create(hash(1025), vec![], U256::zero(), 100_000, None, None, config);
+		assert_eq!(create.map(|_| ()), rejected);
+		let create2 = PrecompileSourceRunner::<Runtime>::create2(
+			hash(2048),
+			vec![],
+			H256::zero(),
+			U256::zero(),
+			100_000,
+			None,
+			None,
+			config,
+		);
+		assert_eq!(create2.map(|_| ()), rejected);
+
+		assert!(call(ALICE).is_ok());
+	});
+}
+
+#[test]
+fn identity_structs_match_solidity_encoding() {
+	let info = Info {
+		display: Bytes(b"alice".to_vec()),
+		..Default::default()
+	};
+	let judgement = RegistrarJudgement {
+		registrar_index: 3,
+		judgement: 1,
+		fee: U256::from(50),
+	};
+	let encoded = EvmDataWriter::new()
+		.write(true)
+		.write(info.clone())
+		.write(vec![judgement.clone()])
+		.build();
+	let words: Vec<U256> = encoded.chunks(abi::WORD).map(U256::from_big_endian).collect();
+
+	// The struct has dynamic members, so it is written after the head, and its
+	// members' offsets are relative to its start.
+	assert_eq!(words[1], U256::from(0x60));
+	assert_eq!(words[3], U256::from(7 * abi::WORD));
+	assert_eq!(words[3 + 7], U256::from(5));
+	// The judgements are static, so they are written in place in the array.
+	let array = words[2].as_usize() / abi::WORD;
+	assert_eq!(&words[array..], &[
+		U256::one(),
+		U256::from(3),
+		U256::one(),
+		U256::from(50)
+	]);
+
+	let mut reader = EvmDataReader::new(&encoded);
+	assert_eq!(reader.read::<bool>(), Ok(true));
+	assert_eq!(reader.read::<Info>(), Ok(info));
+	assert_eq!(reader.read::<Vec<RegistrarJudgement>>(), Ok(vec![judgement]));
+}
+
+#[test]
+fn set_and_clear_identity_through_calldata() {
+	new_test_ext().execute_with(|| {
+		let info = Info {
+			display: Bytes(b"alice".to_vec()),
+			legal: Bytes(b"Alice Liddell".to_vec()),
+			web: Bytes(b"https://alice.example".to_vec()),
+			twitter: Bytes(b"@alice".to_vec()),
+			..Default::default()
+		};
+		let set = EvmDataWriter::new_with_selector(SELECTOR_SET_IDENTITY)
+			.write(info.clone())
+			.build();
+		assert!(identity(ALICE, set).is_ok());
+
+		let of_address = EvmDataWriter::new_with_selector(SELECTOR_IDENTITY_OF)
+			.write(ALICE)
+			.build();
+		assert_eq!(
+			read_identity(identity(BOB, of_address).unwrap()),
+			(true, info.clone(), vec![])
+		);
+		let of_account = EvmDataWriter::new_with_selector(SELECTOR_IDENTITY_OF_ACCOUNT)
+			.write_encoded(&account(ALICE))
+			.build();
+		assert_eq!(read_identity(identity(BOB, of_account).unwrap()), (true, info, vec![]));
+
+		let clear = EvmDataWriter::new_with_selector(SELECTOR_CLEAR_IDENTITY).build();
+		assert!(identity(ALICE, clear).is_ok());
+		let of_address = EvmDataWriter::new_with_selector(SELECTOR_IDENTITY_OF)
+			.write(ALICE)
+			.build();
+		assert_eq!(
+			read_identity(identity(BOB, of_address).unwrap()),
+			(false, Info::default(), vec![])
+		);
+	});
+}
+
+#[test]
+fn request_judgement_through_calldata() {
+	new_test_ext().execute_with(|| {
+		assert!(Identity::add_registrar(Origin::root(), account(BOB)).is_ok());
+		assert!(Identity::set_fee(Origin::signed(account(BOB)), 0, 20).is_ok());
+		let set = EvmDataWriter::new_with_selector(SELECTOR_SET_IDENTITY)
+			.write(Info {
+				display: Bytes(b"alice".to_vec()),
+				..Default::default()
+			})
+			.build();
+		assert!(identity(ALICE, set).is_ok());
+
+		let request = |max_fee: u64| {
+			EvmDataWriter::new_with_selector(SELECTOR_REQUEST_JUDGEMENT)
+				.write(0u32)
+				.write(U256::from(max_fee))
+				.build()
+		};
+		assert!(identity(ALICE, request(10)).is_err());
+		assert!(identity(ALICE, request(20)).is_ok());
+
+		let of_address = EvmDataWriter::new_with_selector(SELECTOR_IDENTITY_OF)
+			.write(ALICE)
+			.build();
+		let (_, _, judgements) = read_identity(identity(BOB, of_address).unwrap());
+		assert_eq!(judgements, vec![RegistrarJudgement {
+			registrar_index: 0,
+			judgement: 1,
+			fee: U256::from(20),
+		}]);
+	});
+}
+
+#[test]
+fn rejects_oversized_identity_fields() {
+	new_test_ext().execute_with(|| {
+		let set = EvmDataWriter::new_with_selector(SELECTOR_SET_IDENTITY)
+			.write(Info {
+				display: Bytes(vec![b'a'; 33]),
+				..Default::default()
+			})
+			.build();
+		assert!(identity(ALICE, set).is_err());
+		assert!(Identity::identity(account(ALICE)).is_none());
+	});
+}
+
+#[test]
+fn identity_rejects_delegate_calls() {
+	new_test_ext().execute_with(|| {
+		let set = EvmDataWriter::new_with_selector(SELECTOR_SET_IDENTITY)
+			.write(Info {
+				display: Bytes(b"alice".to_vec()),
+				..Default::default()
+			})
+			.build();
+		assert_eq!(
+			OnChainIdentity::<Test>::execute(&set, None, &delegated(ALICE)).unwrap_err(),
+			abi::error("cannot be called with DELEGATECALL or CALLCODE")
+		);
+		assert!(Identity::identity(account(ALICE)).is_none());
+	});
+}
+
+#[test]
+fn proxied_calls_are_limited_by_the_proxy_type() {
+	new_test_ext().execute_with(|| {
+		let add_proxy = |proxy_type: u8| {
+			EvmDataWriter::new_with_selector(SELECTOR_ADD_PROXY)
+				.write_encoded(&account(ALICE))
+				.write(proxy_type)
+				.write(0u32)
+				.build()
+		};
+		let proxy_executed = |ok: bool| {
+			System::events().iter().any(|record| match &record.event {
+				Event::pallet_proxy(pallet_proxy::Event::ProxyExecuted(result)) => result.is_ok() == ok,
+				_ => false,
+			})
+		};
+
+		// Both calls pass the dispatch filter, but only the second is governance.
+		assert!(proxy_multisig(BOB, add_proxy(ProxyType::Governance as u8)).is_ok());
+		let remark = Call::System(frame_system::Call::remark(vec![]));
+		assert!(proxy_multisig(ALICE, proxy_call(BOB, remark)).is_ok());
+		assert!(proxy_executed(false));
+		assert!(!proxy_executed(true));
+
+		System::reset_events();
+		let propose = Call::Democracy(pallet_democracy::Call::propose(H256::repeat_byte(1), 100));
+		assert!(proxy_multisig(ALICE, proxy_call(BOB, propose)).is_ok());
+		assert!(proxy_executed(true));
+		assert_eq!(Democracy::public_prop_count(), 1);
+		assert_eq!(Balances::reserved_balance(account(BOB)), 100 + 10 + 1);
+
+		let remove_proxy = EvmDataWriter::new_with_selector(SELECTOR_REMOVE_PROXY)
+			.write_encoded(&account(ALICE))
+			.write(ProxyType::Governance as u8)
+			.write(0u32)
+			.build();
+		assert!(proxy_multisig(BOB, remove_proxy).is_ok());
+		assert_eq!(Balances::reserved_balance(account(BOB)), 100);
+
+		assert!(proxy_multisig(BOB, add_proxy(7)).is_err());
+	});
+}
+
+#[test]
+fn proxied_calls_must_pass_the_dispatch_filter() {
+	new_test_ext().execute_with(|| {
+		let charlie = account(H160::repeat_byte(0xcc));
+		let transfer = Call::Balances(pallet_balances::Call::transfer(charlie.clone(), 10));
+		let add_proxy = EvmDataWriter::new_with_selector(SELECTOR_ADD_PROXY)
+			.write_encoded(&account(ALICE))
+			.write(ProxyType::Any as u8)
+			.write(0u32)
+			.build();
+		assert!(proxy_multisig(BOB, add_proxy).is_ok());
+
+		assert_eq!(
+			proxy_multisig(ALICE, proxy_call(BOB, transfer.clone())).unwrap_err(),
+			abi::error("call Balances.transfer is not allowed")
+		);
+		let remark = Call::System(frame_system::Call::remark(vec![]));
+		let batch = Call::Utility(pallet_utility::Call::batch(vec![remark, transfer.clone()]));
+		assert_eq!(
+			proxy_multisig(ALICE, proxy_call(BOB, batch)).unwrap_err(),
+			abi::error("call Balances.transfer is not allowed")
+		);
+		assert_eq!(Balances::free_balance(&charlie), 0);
+		assert!(System::events().iter().any(|record| record.event
+			== Event::evm_dispatch(evm_dispatch::Event::CallRejected(
+				account(ALICE),
+				b"Balances".to_vec(),
+				b"transfer".to_vec(),
+			))));
+
+		let as_multi = EvmDataWriter::new_with_selector(SELECTOR_AS_MULTI)
+			.write(2u16)
+			.write(vec![bytes32(&account(BOB))])
+			.write(TimepointData::default())
+			.write(Bytes(transfer.encode()))
+			.write(true)
+			.write(1_000_000_000u64)
+			.build();
+		assert_eq!(
+			proxy_multisig(ALICE, as_multi).unwrap_err(),
+			abi::error("call Balances.transfer is not allowed")
+		);
+
+		// A Substrate signer stores the call, so that the approval of the EVM
+		// caller would complete the multisig and execute it.
+		let store = pallet_multisig::Call::as_multi(2, vec![account(ALICE)], None, transfer.encode(), true, 0);
+		Call::Multisig(store).dispatch(Origin::signed(account(BOB))).unwrap();
+		let approve = EvmDataWriter::new_with_selector(SELECTOR_APPROVE_AS_MULTI)
+			.write(2u16)
+			.write(vec![bytes32(&account(BOB))])
+			.write(TimepointData { height: 1, index: 0 })
+			.write(H256(sp_io::hashing::blake2_256(&transfer.encode())))
+			.write(1_000_000_000u64)
+			.build();
+		assert_eq!(
+			proxy_multisig(ALICE, approve).unwrap_err(),
+			abi::error("call Balances.transfer is not allowed")
+		);
+	});
+}
+
+#[test]
+fn proxy_multisig_rejects_delegate_calls() {
+	new_test_ext().execute_with(|| {
+		let add_proxy = EvmDataWriter::new_with_selector(SELECTOR_ADD_PROXY)
+			.write_encoded(&account(BOB))
+			.write(ProxyType::Any as u8)
+			.write(0u32)
+			.build();
+		assert_eq!(
+			ProxyMultisig::<Test>::execute(&add_proxy, None, &delegated(ALICE)).unwrap_err(),
+			abi::error("cannot be called with DELEGATECALL or CALLCODE")
+		);
+		assert!(Proxy::proxies(account(ALICE)).0.is_empty());
+	});
+}
+
+#[test]
+fn multisig_approvals_through_calldata() {
+	new_test_ext().execute_with(|| {
+		let call = Call::System(frame_system::Call::remark(b"approved".to_vec())).encode();
+		let call_hash = H256(sp_io::hashing::blake2_256(&call));
+		let approve = |caller: H160, other: H160| {
+			let input = EvmDataWriter::new_with_selector(SELECTOR_APPROVE_AS_MULTI)
+				.write(2u16)
+				.write(vec![H256::from_slice(&account(other).encode())])
+				.write(TimepointData::default())
+				.write(call_hash)
+				.write(1_000_000_000u64)
+				.build();
+			proxy_multisig(caller, input)
+		};
+		let timepoint = TimepointData { height: 1, index: 0 };
+		let multisig_event = |matches: fn(&pallet_multisig::Event<Test>) -> bool| {
+			System::events().iter().any(|record| match &record.event {
+				Event::pallet_multisig(event) => matches(event),
+				_ => false,
+			})
+		};
+
+		assert!(approve(ALICE, BOB).is_ok());
+		assert!(multisig_event(|event| matches!(
+			event,
+			pallet_multisig::Event::NewMultisig(..)
+		)));
+		let cancel = EvmDataWriter::new_with_selector(SELECTOR_CANCEL_AS_MULTI)
+			.write(2u16)
+			.write(vec![H256::from_slice(&account(BOB).encode())])
+			.write(timepoint)
+			.write(call_hash)
+			.build();
+		assert!(proxy_multisig(ALICE, cancel).is_ok());
+		assert!(multisig_event(|event| matches!(
+			event,
+			pallet_multisig::Event::MultisigCancelled(..)
+		)));
+
+		assert!(approve(ALICE, BOB).is_ok());
+		let as_multi = EvmDataWriter::new_with_selector(SELECTOR_AS_MULTI)
+			.write(2u16)
+			.write(vec![H256::from_slice(&account(ALICE).encode())])
+			.write(timepoint)
+			.write(Bytes(call))
+			.write(false)
+			.write(1_000_000_000u64)
+			.build();
+		assert!(proxy_multisig(BOB, as_multi).is_ok());
+		assert!(multisig_event(|event| matches!(
+			event,
+			pallet_multisig::Event::MultisigExecuted(_, _, _, _, Ok(()))
+		)));
+	});
+}
+
+#[test]
+fn randomness_differs_by_subject_and_block() {
+	new_test_ext().execute_with(|| {
+		start_block(1);
+		let (first, block) = random(b"first");
+		let (second, _) = random(b"second");
+		assert_ne!(first, second);
+		assert_eq!(random(b"first"), (first, block));
+
+		start_block(2);
+		let (later, _) = random(b"first");
+		assert_ne!(later, first);
+		assert_ne!(later, second);
+	});
+}
+
+#[test]
+fn randomness_matches_the_pallet() {
+	new_test_ext().execute_with(|| {
+		for number in 1..5 {
+			start_block(number);
+		}
+		let (randomness, block) = RandomnessCollectiveFlip::random(b"edgeware");
+		assert_eq!(random(b"edgeware"), (randomness, U256::from(block)));
+	});
+}
+
+#[test]
+fn wasm_bridge_returns_the_contract_output() {
+	new_test_ext().execute_with(|| {
+		let echo = deploy_wasm(WASM_ECHO);
+		let output = wasm_bridge(BOB, &echo, vec![0, 1, 2, 3], 0).unwrap();
+		assert!(output.cost > 0);
+		let Bytes(data) = EvmDataReader::new(&output.output).read().unwrap();
+		assert_eq!(data, vec![0, 1, 2, 3]);
+	});
+}
+
+#[test]
+fn wasm_bridge_fails_when_the_contract_does() {
+	new_test_ext().execute_with(|| {
+		let echo = deploy_wasm(WASM_ECHO);
+		assert_eq!(
+			wasm_bridge(BOB, &echo, vec![1, 2, 3], 0).unwrap_err(),
+			ExitError::Other("wasm contract reverted".into())
+		);
+		assert!(wasm_bridge(BOB, &account(ALICE), vec![0], 0).is_err());
+	});
+}
+
+#[test]
+fn wasm_bridge_forwards_value() {
+	new_test_ext().execute_with(|| {
+		let echo = deploy_wasm(WASM_ECHO);
+		// The EVM moves the value to the precompile before calling it.
+		Balances::make_free_balance_be(&account(hash(2054)), 500);
+		let before = Balances::free_balance(&echo);
+		assert!(wasm_bridge(BOB, &echo, vec![0], 500).is_ok());
+		assert_eq!(Balances::free_balance(&echo), before + 500);
+		assert_eq!(Balances::free_balance(&account(hash(2054))), 0);
+	});
+}
+
+#[test]
+fn wasm_bridge_rejects_delegate_calls() {
+	new_test_ext().execute_with(|| {
+		let echo = deploy_wasm(WASM_ECHO);
+		let input = EvmDataWriter::new_with_selector(SELECTOR_CALL)
+			.write_encoded(&echo)
+			.write(Bytes(vec![0]))
+			.build();
+		assert_eq!(
+			WasmBridge::<Test>::execute(&input, Some(GAS_LIMIT), &delegated(BOB)).unwrap_err(),
+			abi::error("cannot be called with DELEGATECALL or CALLCODE")
+		);
+	});
+}
+
+#[test]
+fn evm_extension_calls_evm_contracts() {
+	new_test_ext().execute_with(|| {
+		let caller = deploy_wasm(WASM_EVM_CALLER);
+		pallet_evm::AccountCodes::<Test>::insert(BOB, EVM_ECHO.to_vec());
+		pallet_evm::AccountCodes::<Test>::insert(ALICE, EVM_REVERT.to_vec());
+
+		assert_eq!(
+			call_evm_from_wasm(&caller, BOB, vec![1, 2, 3], 100_000),
+			Some((EVM_CALL_SUCCEEDED, vec![1, 2, 3]))
+		);
+		assert_eq!(
+			call_evm_from_wasm(&caller, ALICE, vec![4, 5], 100_000),
+			Some((EVM_CALL_REVERTED, vec![4, 5]))
+		);
+		// Running out of gas traps the Wasm contract.
+		assert_eq!(call_evm_from_wasm(&caller, BOB, vec![1, 2, 3], 1), None);
+	});
+}
+
+#[test]
+fn calls_between_vms_cannot_nest() {
+	new_test_ext().execute_with(|| {
+		let caller = deploy_wasm(WASM_EVM_CALLER);
+		pallet_evm::AccountCodes::<Test>::insert(BOB, EVM_ECHO.to_vec());
+		let call = EvmCall {
+			target: BOB,
+			input: vec![1],
+			value: 0u128,
+			gas_limit: 100_000,
+		};
+		assert!(wasm_bridge(ALICE, &caller, call.encode(), 0).is_err());
+		// The failed call does not leave later calls refused.
+		assert_eq!(
+			call_evm_from_wasm(&caller, BOB, vec![1], 100_000),
+			Some((EVM_CALL_SUCCEEDED, vec![1]))
+		);
+	});
+}
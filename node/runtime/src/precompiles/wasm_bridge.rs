@@ -0,0 +1,230 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Calls between EVM contracts and `pallet_contracts` (ink!) contracts.
+//!
+//! [`WasmBridge`] is a precompile that calls a Wasm contract with the EVM
+//! caller's mapped account as the caller, forwarding the call's value and
+//! remaining gas, and returns the contract's output. A Wasm contract that
+//! reverts or fails fails the EVM call; a precompile cannot return revert
+//! data, so the contract's output is lost.
+//!
+//! ```solidity
+//! interface WasmBridge {
+//!     // `input` is the SCALE-encoded selector and arguments of the message
+//!     function call(bytes32 dest, bytes calldata input) external payable returns (bytes memory);
+//! }
+//! ```
+//!
+//! [`EvmExtension`] is a chain extension that lets Wasm contracts call EVM
+//! contracts, from the address made of the first 20 bytes of their account,
+//! as `pallet_evm::call` does for Substrate accounts. Its input is a
+//! SCALE-encoded [`EvmCall`], and the value is moved from the Wasm contract to
+//! the account that address maps to before the call. It returns `0` with the
+//! EVM contract's output if the call succeeds, or `1` with the revert data if
+//! it reverts, in which case the value is moved back. A call that fails in any
+//! other way traps the Wasm contract.
+//!
+//! Calls in either direction cannot nest: a Wasm contract called from the EVM
+//! cannot call back into the EVM, and the other way round.
+
+use super::{
+	abi::{self, error, Bytes, EvmDataReader, EvmDataWriter, EvmResult},
+	dispatch::{self, Gasometer},
+	hash,
+};
+use codec::{Decode, Encode};
+use evm::{executor::PrecompileOutput, Context, ExitReason};
+use frame_support::traits::{Currency, ExistenceRequirement, Get};
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RetVal, SysConfig, UncheckedFrom,
+};
+use pallet_evm::{AddressMapping, GasWeightMapping, Precompile, Runner};
+use sp_core::H160;
+use sp_runtime::{traits::Zero, DispatchError};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// `call(bytes32,bytes)`
+pub const SELECTOR_CALL: u32 = 0x3ae7af08;
+
+/// The function id of [`EvmExtension`]'s call.
+pub const FUNC_EVM_CALL: u32 = 1;
+
+/// Returned by [`EvmExtension`] when the EVM call succeeded.
+pub const EVM_CALL_SUCCEEDED: u32 = 0;
+/// Returned by [`EvmExtension`] when the EVM call reverted.
+pub const EVM_CALL_REVERTED: u32 = 1;
+
+// Set while a call between the two kinds of contract is in progress. It is
+// kept in memory rather than in storage, so it costs no weight and is gone
+// however the call ends.
+environmental::environmental!(in_cross_vm_call: ());
+
+/// Runs `f` as a call between the two kinds of contract, failing if one is
+/// already in progress. Nested calls would each run a new executor on the
+/// runtime's stack, bounded only by gas.
+fn cross_vm_call<T>(f: impl FnOnce() -> T) -> Result<T, &'static str> {
+	if in_cross_vm_call::with(|_| ()).is_some() {
+		return Err("calls between EVM and Wasm contracts cannot nest");
+	}
+	Ok(in_cross_vm_call::using(&mut (), f))
+}
+
+type ContractsBalanceOf<R> =
+	<<R as pallet_contracts::Config>::Currency as Currency<<R as frame_system::Config>::AccountId>>::Balance;
+type EvmBalanceOf<R> =
+	<<R as pallet_evm::Config>::Currency as Currency<<R as frame_system::Config>::AccountId>>::Balance;
+
+/// The precompile calling Wasm contracts.
+pub struct WasmBridge<R>(PhantomData<R>);
+
+impl<R> Precompile for WasmBridge<R>
+where
+	R: pallet_contracts::Config + pallet_evm::Config,
+	R::AccountId: UncheckedFrom<R::Hash> + AsRef<[u8]>,
+{
+	fn execute(input: &[u8], target_gas: Option<u64>, context: &Context) -> EvmResult<PrecompileOutput> {
+		dispatch::ensure_direct_call(context, hash(2054))?;
+		let mut gasometer = Gasometer::new(target_gas);
+		let (selector, mut input) = EvmDataReader::new_with_selector(input)?;
+		if selector != SELECTOR_CALL {
+			return Err(error("unknown wasm bridge selector"));
+		}
+		let dest = input.read_decoded()?;
+		let Bytes(data) = input.read()?;
+		let caller = R::AddressMapping::into_account_id(context.caller);
+		let value: ContractsBalanceOf<R> = abi::to_balance(context.apparent_value)?;
+
+		// The EVM has already moved the value to this precompile's account, so
+		// it is moved back to the caller to be sent on.
+		gasometer.record_weight::<R>(R::DbWeight::get().reads_writes(2, 4))?;
+		if !value.is_zero() {
+			let amount: EvmBalanceOf<R> = abi::to_balance(context.apparent_value)?;
+			<R as pallet_evm::Config>::Currency::transfer(
+				&R::AddressMapping::into_account_id(context.address),
+				&caller,
+				amount,
+				ExistenceRequirement::AllowDeath,
+			)
+			.map_err(|e| error(e.into()))?;
+		}
+
+		let gas_limit = R::GasWeightMapping::gas_to_weight(gasometer.remaining().unwrap_or(u64::MAX));
+		let result =
+			cross_vm_call(|| pallet_contracts::Pallet::<R>::bare_call(caller, dest, value, gas_limit, data, false))
+				.map_err(error)?;
+		gasometer.record_weight::<R>(result.gas_consumed)?;
+		match result.result {
+			Ok(output) if output.did_revert() => Err(error("wasm contract reverted")),
+			Ok(output) => Ok(dispatch::succeed(
+				&gasometer,
+				EvmDataWriter::new().write(Bytes(output.data)).build(),
+			)),
+			Err(e) => Err(error(e.into())),
+		}
+	}
+}
+
+/// The input of [`EvmExtension`]'s call.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, Debug)]
+pub struct EvmCall<Balance> {
+	/// The EVM contract to call.
+	pub target: H160,
+	/// The call data.
+	pub input: Vec<u8>,
+	/// The value to send, from the Wasm contract's balance.
+	pub value: Balance,
+	/// The most gas the call may use.
+	pub gas_limit: u64,
+}
+
+/// The chain extension calling EVM contracts.
+pub struct EvmExtension<R>(PhantomData<R>);
+
+impl<R> Default for EvmExtension<R> {
+	fn default() -> Self {
+		EvmExtension(PhantomData)
+	}
+}
+
+impl<R> ChainExtension<R> for EvmExtension<R>
+where
+	R: pallet_contracts::Config + pallet_evm::Config,
+{
+	fn call<E>(func_id: u32, env: Environment<E, InitState>) -> Result<RetVal, DispatchError>
+	where
+		E: Ext<T = R>,
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		if func_id != FUNC_EVM_CALL {
+			return Err(DispatchError::Other("unknown chain extension function"));
+		}
+		let mut env = env.buf_in_buf_out();
+		let in_len = env.in_len();
+		let call = EvmCall::<EvmBalanceOf<R>>::decode(&mut &env.read(in_len)?[..])
+			.map_err(|_| DispatchError::Other("evm call could not be decoded"))?;
+		let charged = env.charge_weight(
+			R::GasWeightMapping::gas_to_weight(call.gas_limit) + R::DbWeight::get().reads_writes(2, 4),
+		)?;
+
+		let contract = env.ext().address().clone();
+		let source = H160::from_slice(&contract.as_ref()[..20]);
+		let source_account = R::AddressMapping::into_account_id(source);
+		let value = abi::from_balance(call.value);
+		if !call.value.is_zero() {
+			<R as pallet_evm::Config>::Currency::transfer(
+				&contract,
+				&source_account,
+				call.value,
+				ExistenceRequirement::KeepAlive,
+			)?;
+		}
+
+		let info = cross_vm_call(|| {
+			R::Runner::call(
+				source,
+				call.target,
+				call.input,
+				value,
+				call.gas_limit,
+				None,
+				None,
+				R::config(),
+			)
+			.map_err(Into::into)
+		})??;
+		env.adjust_weight(
+			charged,
+			R::GasWeightMapping::gas_to_weight(info.used_gas.low_u64()) + R::DbWeight::get().reads_writes(2, 4),
+		);
+
+		let status = match info.exit_reason {
+			ExitReason::Succeed(_) => EVM_CALL_SUCCEEDED,
+			ExitReason::Revert(_) => EVM_CALL_REVERTED,
+			ExitReason::Error(_) | ExitReason::Fatal(_) => return Err(DispatchError::Other("evm call failed")),
+		};
+		if status == EVM_CALL_REVERTED && !call.value.is_zero() {
+			<R as pallet_evm::Config>::Currency::transfer(
+				&source_account,
+				&contract,
+				call.value,
+				ExistenceRequirement::AllowDeath,
+			)?;
+		}
+		env.write(&info.value, false, None)?;
+		Ok(RetVal::Converging(status))
+	}
+}
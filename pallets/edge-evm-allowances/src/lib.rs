@@ -0,0 +1,93 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! ERC-20 allowances for tokens exposed to the EVM through precompiles.
+//!
+//! Balances live in the pallets backing each token, this pallet only records
+//! how much of an owner's token a spender may move with `transferFrom`. Tokens,
+//! owners and spenders are all identified by their EVM address.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+pub mod mock;
+
+#[cfg(test)]
+pub mod tests;
+
+use sp_core::{H160, U256};
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+
+	/// The pallet's configuration trait.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The spender's allowance is lower than the amount.
+		InsufficientAllowance,
+	}
+
+	/// How much of an owner's token a spender may transfer, keyed by
+	/// `(token, owner)` and then spender.
+	#[pallet::storage]
+	pub type Allowances<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, (H160, H160), Blake2_128Concat, H160, U256, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {}
+
+	impl<T: Config> Pallet<T> {
+		/// How much of `owner`'s `token` `spender` may transfer.
+		pub fn allowance(token: H160, owner: H160, spender: H160) -> U256 {
+			Allowances::<T>::get((token, owner), spender)
+		}
+
+		/// Sets how much of `owner`'s `token` `spender` may transfer,
+		/// replacing any earlier allowance.
+		pub fn approve(token: H160, owner: H160, spender: H160, amount: U256) {
+			if amount.is_zero() {
+				Allowances::<T>::remove((token, owner), spender);
+			} else {
+				Allowances::<T>::insert((token, owner), spender, amount);
+			}
+		}
+
+		/// Uses up `amount` of `spender`'s allowance. An allowance of
+		/// `U256::MAX` is unlimited and never used up.
+		pub fn spend(token: H160, owner: H160, spender: H160, amount: U256) -> DispatchResult {
+			let allowance = Self::allowance(token, owner, spender);
+			if allowance == U256::max_value() {
+				return Ok(());
+			}
+			let remaining = allowance.checked_sub(amount).ok_or(Error::<T>::InsufficientAllowance)?;
+			Self::approve(token, owner, spender, remaining);
+			Ok(())
+		}
+	}
+}
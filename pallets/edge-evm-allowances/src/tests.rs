@@ -0,0 +1,58 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::mock::*;
+use frame_support::{assert_noop, assert_ok};
+
+#[test]
+fn approve_replaces_allowance() {
+	new_test_ext().execute_with(|| {
+		EvmAllowances::approve(TOKEN, OWNER, SPENDER, 100.into());
+		EvmAllowances::approve(TOKEN, OWNER, SPENDER, 40.into());
+		assert_eq!(EvmAllowances::allowance(TOKEN, OWNER, SPENDER), 40.into());
+		// Allowances are per token and per owner
+		assert_eq!(EvmAllowances::allowance(SPENDER, OWNER, SPENDER), 0.into());
+		assert_eq!(EvmAllowances::allowance(TOKEN, SPENDER, OWNER), 0.into());
+
+		EvmAllowances::approve(TOKEN, OWNER, SPENDER, 0.into());
+		assert!(!Allowances::<Test>::contains_key((TOKEN, OWNER), SPENDER));
+	});
+}
+
+#[test]
+fn spend_uses_up_allowance() {
+	new_test_ext().execute_with(|| {
+		EvmAllowances::approve(TOKEN, OWNER, SPENDER, 100.into());
+		assert_ok!(EvmAllowances::spend(TOKEN, OWNER, SPENDER, 60.into()));
+		assert_eq!(EvmAllowances::allowance(TOKEN, OWNER, SPENDER), 40.into());
+		assert_noop!(
+			EvmAllowances::spend(TOKEN, OWNER, SPENDER, 41.into()),
+			Error::<Test>::InsufficientAllowance
+		);
+		assert_ok!(EvmAllowances::spend(TOKEN, OWNER, SPENDER, 40.into()));
+		assert!(!Allowances::<Test>::contains_key((TOKEN, OWNER), SPENDER));
+	});
+}
+
+#[test]
+fn unlimited_allowance_is_never_used_up() {
+	new_test_ext().execute_with(|| {
+		EvmAllowances::approve(TOKEN, OWNER, SPENDER, U256::max_value());
+		assert_ok!(EvmAllowances::spend(TOKEN, OWNER, SPENDER, 1_000.into()));
+		assert_eq!(EvmAllowances::allowance(TOKEN, OWNER, SPENDER), U256::max_value());
+	});
+}
@@ -0,0 +1,101 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Decides which runtime calls EVM contracts may dispatch through the
+//! Dispatch precompile, and records the calls it turns away.
+//!
+//! `Config::CallFilter` is an allowlist in the style of a proxy type. `Utility`
+//! batches are looked through rather than filtered themselves: a batch is
+//! allowed only if every call in it is, so a disallowed call cannot be
+//! smuggled inside one.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+pub mod mock;
+
+#[cfg(test)]
+pub mod tests;
+
+use frame_support::traits::{CallMetadata, GetCallMetadata, InstanceFilter, IsSubType, IsType};
+use sp_std::vec::Vec;
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+
+	/// The pallet's configuration trait.
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_utility::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The calls contracts may dispatch.
+		type CallFilter: InstanceFilter<<Self as frame_system::Config>::Call> + Default;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::AccountId = "AccountId")]
+	pub enum Event<T: Config> {
+		/// A contract tried to dispatch a call the filter does not allow.
+		/// \[who, pallet, call\]
+		CallRejected(T::AccountId, Vec<u8>, Vec<u8>),
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {}
+
+	impl<T: Config> Pallet<T> {
+		/// The first call in `call` the filter does not allow, looking inside
+		/// `Utility` batches, or `None` if it may be dispatched.
+		pub fn rejected_call(call: &<T as frame_system::Config>::Call) -> Option<&<T as frame_system::Config>::Call> {
+			match <T as pallet_utility::Config>::Call::from_ref(call).is_sub_type() {
+				Some(pallet_utility::Call::batch(calls)) | Some(pallet_utility::Call::batch_all(calls)) => {
+					calls.iter().find_map(|call| Self::rejected_call(call.into_ref()))
+				}
+				Some(pallet_utility::Call::as_derivative(_, call)) => Self::rejected_call(call.as_ref().into_ref()),
+				_ if T::CallFilter::default().filter(call) => None,
+				_ => Some(call),
+			}
+		}
+
+		/// Records that `who` tried to dispatch the disallowed `call`.
+		pub fn note_rejected(who: T::AccountId, call: &<T as frame_system::Config>::Call)
+		where
+			<T as frame_system::Config>::Call: GetCallMetadata,
+		{
+			let CallMetadata {
+				pallet_name,
+				function_name,
+			} = call.get_call_metadata();
+			Self::deposit_event(Event::CallRejected(
+				who,
+				pallet_name.as_bytes().to_vec(),
+				function_name.as_bytes().to_vec(),
+			));
+		}
+	}
+}
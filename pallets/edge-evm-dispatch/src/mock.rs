@@ -0,0 +1,115 @@
+use super::*;
+use crate as evm_dispatch;
+use frame_support::{construct_runtime, parameter_types};
+use frame_system::mocking::{MockBlock, MockUncheckedExtrinsic};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+
+pub type AccountId = u64;
+pub type Balance = u64;
+pub type BlockNumber = u64;
+
+// Configure a mock runtime to test the pallet.
+type UncheckedExtrinsic = MockUncheckedExtrinsic<Test>;
+type Block = MockBlock<Test>;
+
+construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Utility: pallet_utility::{Pallet, Call, Event},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		EvmDispatch: evm_dispatch::{Pallet, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type AccountId = AccountId;
+	type BaseCallFilter = ();
+	type BlockHashCount = BlockHashCount;
+	type BlockLength = ();
+	type BlockNumber = BlockNumber;
+	type BlockWeights = ();
+	type Call = Call;
+	type DbWeight = ();
+	type Event = Event;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type Header = Header;
+	type Index = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type OnKilledAccount = ();
+	type OnNewAccount = ();
+	type OnSetCode = ();
+	type Origin = Origin;
+	type PalletInfo = PalletInfo;
+	type SS58Prefix = ();
+	type SystemWeightInfo = ();
+	type Version = ();
+}
+
+impl pallet_utility::Config for Test {
+	type Call = Call;
+	type Event = Event;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+	pub const MaxLocks: u32 = 50;
+}
+
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+	type Balance = Balance;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type MaxLocks = MaxLocks;
+	type WeightInfo = ();
+}
+
+/// Allows `System::remark` only.
+#[derive(Clone, Copy, Default)]
+pub struct RemarkOnly;
+impl InstanceFilter<Call> for RemarkOnly {
+	fn filter(&self, call: &Call) -> bool {
+		matches!(call, Call::System(frame_system::Call::remark(..)))
+	}
+}
+
+impl Config for Test {
+	type CallFilter = RemarkOnly;
+	type Event = Event;
+}
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap()
+		.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+pub fn remark() -> Call {
+	Call::System(frame_system::Call::remark(b"hello".to_vec()))
+}
+
+pub fn transfer() -> Call {
+	Call::Balances(pallet_balances::Call::transfer(BOB, 10))
+}
@@ -0,0 +1,58 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::mock::*;
+
+#[test]
+fn filters_plain_calls() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(EvmDispatch::rejected_call(&remark()), None);
+		assert_eq!(EvmDispatch::rejected_call(&transfer()), Some(&transfer()));
+	});
+}
+
+#[test]
+fn looks_inside_batches() {
+	new_test_ext().execute_with(|| {
+		let allowed = Call::Utility(pallet_utility::Call::batch(vec![remark(), remark()]));
+		assert_eq!(EvmDispatch::rejected_call(&allowed), None);
+
+		let smuggled = Call::Utility(pallet_utility::Call::batch_all(vec![remark(), transfer()]));
+		assert_eq!(EvmDispatch::rejected_call(&smuggled), Some(&transfer()));
+
+		let nested = Call::Utility(pallet_utility::Call::as_derivative(
+			0,
+			Box::new(Call::Utility(pallet_utility::Call::batch(vec![transfer()]))),
+		));
+		assert_eq!(EvmDispatch::rejected_call(&nested), Some(&transfer()));
+	});
+}
+
+#[test]
+fn note_rejected_names_the_call() {
+	new_test_ext().execute_with(|| {
+		EvmDispatch::note_rejected(ALICE, &transfer());
+		assert_eq!(
+			System::events().pop().expect("Event expected").event,
+			mock::Event::evm_dispatch(crate::Event::CallRejected(
+				ALICE,
+				b"Balances".to_vec(),
+				b"transfer".to_vec()
+			)),
+		);
+	});
+}
@@ -0,0 +1,154 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Selects the hard fork whose rules the EVM runs by, and switches to a new
+//! one at a block chosen by governance.
+//!
+//! A runtime using it returns [`Pallet::evm_config`] from its
+//! `pallet_evm::Config::config`. Root schedules a fork with
+//! [`Pallet::schedule_hard_fork`], and it takes effect from the start of the
+//! scheduled block, so every transaction in a block runs by the same rules.
+//! Forks the runtime has no config for cannot be scheduled.
+//!
+//! The Edgeware runtime does not include this pallet yet. The `evm` crate its
+//! Frontier links predates Berlin and London and has no config for either, so
+//! the runtime stays on Istanbul until both are upgraded.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+pub mod mock;
+
+#[cfg(test)]
+pub mod tests;
+
+use codec::{Decode, Encode};
+use evm_runtime::Config as EvmConfig;
+use sp_runtime::RuntimeDebug;
+
+pub use pallet::*;
+
+/// An Ethereum hard fork, by name.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub enum HardFork {
+	Istanbul,
+	Berlin,
+	/// London, without the EIP-1559 base fee.
+	London,
+}
+
+impl Default for HardFork {
+	fn default() -> Self {
+		HardFork::Istanbul
+	}
+}
+
+/// The EVM configs a runtime can run.
+pub trait HardForkConfigs {
+	/// The config for `fork`, or `None` if the runtime cannot run it. Istanbul
+	/// must always have one.
+	fn config(fork: HardFork) -> Option<&'static EvmConfig>;
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	/// The pallet's configuration trait.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The EVM config of each hard fork.
+		type Configs: HardForkConfigs;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::BlockNumber = "BlockNumber")]
+	pub enum Event<T: Config> {
+		/// A hard fork was scheduled. \[fork, block\]
+		HardForkScheduled(HardFork, T::BlockNumber),
+		/// The EVM now runs by a hard fork's rules. \[fork\]
+		HardForkActivated(HardFork),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The runtime has no EVM config for the hard fork.
+		UnsupportedHardFork,
+		/// The block has already started.
+		BlockInPast,
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	/// The hard fork the EVM runs by.
+	#[pallet::storage]
+	#[pallet::getter(fn active_hard_fork)]
+	pub type ActiveHardFork<T> = StorageValue<_, HardFork, ValueQuery>;
+
+	/// The next hard fork, and the block it takes effect at.
+	#[pallet::storage]
+	#[pallet::getter(fn scheduled_hard_fork)]
+	pub type ScheduledHardFork<T: Config> = StorageValue<_, (T::BlockNumber, HardFork), OptionQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			match ScheduledHardFork::<T>::get() {
+				Some((at, fork)) if at <= n => {
+					ScheduledHardFork::<T>::kill();
+					ActiveHardFork::<T>::put(fork);
+					Self::deposit_event(Event::HardForkActivated(fork));
+					T::DbWeight::get().reads_writes(1, 2)
+				}
+				_ => T::DbWeight::get().reads(1),
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Switch the EVM to `fork`'s rules from the start of block `at`,
+		/// replacing any fork already scheduled.
+		///
+		/// The dispatch origin must be root.
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn schedule_hard_fork(origin: OriginFor<T>, at: T::BlockNumber, fork: HardFork) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(T::Configs::config(fork).is_some(), Error::<T>::UnsupportedHardFork);
+			ensure!(at > frame_system::Pallet::<T>::block_number(), Error::<T>::BlockInPast);
+			ScheduledHardFork::<T>::put((at, fork));
+			Self::deposit_event(Event::HardForkScheduled(fork, at));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The config of the active hard fork, or Istanbul's if the runtime no
+		/// longer has one for it.
+		pub fn evm_config() -> &'static EvmConfig {
+			T::Configs::config(Self::active_hard_fork())
+				.or_else(|| T::Configs::config(HardFork::Istanbul))
+				.expect("every runtime has an Istanbul config; qed")
+		}
+	}
+}
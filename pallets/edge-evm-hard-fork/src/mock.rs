@@ -0,0 +1,98 @@
+use super::*;
+use crate as evm_hard_fork;
+use frame_support::{construct_runtime, parameter_types, traits::OnInitialize};
+use frame_system::mocking::{MockBlock, MockUncheckedExtrinsic};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+
+pub type AccountId = u64;
+pub type BlockNumber = u64;
+
+// Configure a mock runtime to test the pallet.
+type UncheckedExtrinsic = MockUncheckedExtrinsic<Test>;
+type Block = MockBlock<Test>;
+
+construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		EvmHardFork: evm_hard_fork::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+	type AccountData = ();
+	type AccountId = AccountId;
+	type BaseCallFilter = ();
+	type BlockHashCount = BlockHashCount;
+	type BlockLength = ();
+	type BlockNumber = BlockNumber;
+	type BlockWeights = ();
+	type Call = Call;
+	type DbWeight = ();
+	type Event = Event;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type Header = Header;
+	type Index = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type OnKilledAccount = ();
+	type OnNewAccount = ();
+	type OnSetCode = ();
+	type Origin = Origin;
+	type PalletInfo = PalletInfo;
+	type SS58Prefix = ();
+	type SystemWeightInfo = ();
+	type Version = ();
+}
+
+/// Istanbul, and Istanbul with Berlin's cold access costs standing in for
+/// Berlin. There is no London config.
+pub struct MockConfigs;
+impl HardForkConfigs for MockConfigs {
+	fn config(fork: HardFork) -> Option<&'static EvmConfig> {
+		let config = match fork {
+			HardFork::Istanbul => EvmConfig::istanbul(),
+			HardFork::Berlin => EvmConfig {
+				gas_sload: 2100,
+				gas_balance: 2600,
+				gas_call: 2600,
+				gas_ext_code: 2600,
+				gas_ext_code_hash: 2600,
+				..EvmConfig::istanbul()
+			},
+			HardFork::London => return None,
+		};
+		Some(Box::leak(Box::new(config)))
+	}
+}
+
+impl Config for Test {
+	type Configs = MockConfigs;
+	type Event = Event;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap()
+		.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Starts block `number`.
+pub fn run_to_block(number: BlockNumber) {
+	System::set_block_number(number);
+	EvmHardFork::on_initialize(number);
+}
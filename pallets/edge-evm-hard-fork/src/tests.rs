@@ -0,0 +1,81 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::mock::*;
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::DispatchError;
+
+#[test]
+fn starts_on_istanbul() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(EvmHardFork::active_hard_fork(), HardFork::Istanbul);
+		assert_eq!(EvmHardFork::evm_config().gas_sload, 800);
+	});
+}
+
+#[test]
+fn switches_config_at_the_scheduled_block() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(EvmHardFork::schedule_hard_fork(Origin::root(), 3, HardFork::Berlin));
+		assert_eq!(EvmHardFork::scheduled_hard_fork(), Some((3, HardFork::Berlin)));
+
+		run_to_block(2);
+		assert_eq!(EvmHardFork::active_hard_fork(), HardFork::Istanbul);
+		let istanbul = EvmHardFork::evm_config();
+
+		run_to_block(3);
+		assert_eq!(EvmHardFork::active_hard_fork(), HardFork::Berlin);
+		assert_eq!(EvmHardFork::scheduled_hard_fork(), None);
+		let berlin = EvmHardFork::evm_config();
+		assert_eq!((istanbul.gas_sload, berlin.gas_sload), (800, 2100));
+		assert_eq!((istanbul.gas_call, berlin.gas_call), (700, 2600));
+		assert_eq!((istanbul.gas_balance, berlin.gas_balance), (700, 2600));
+		assert_eq!(
+			System::events().pop().expect("Event expected").event,
+			mock::Event::evm_hard_fork(crate::Event::HardForkActivated(HardFork::Berlin)),
+		);
+	});
+}
+
+#[test]
+fn rescheduling_replaces_the_scheduled_fork() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(EvmHardFork::schedule_hard_fork(Origin::root(), 3, HardFork::Berlin));
+		assert_ok!(EvmHardFork::schedule_hard_fork(Origin::root(), 5, HardFork::Istanbul));
+		run_to_block(3);
+		assert_eq!(EvmHardFork::active_hard_fork(), HardFork::Istanbul);
+		assert_eq!(EvmHardFork::scheduled_hard_fork(), Some((5, HardFork::Istanbul)));
+	});
+}
+
+#[test]
+fn only_root_schedules_supported_forks() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			EvmHardFork::schedule_hard_fork(Origin::signed(1), 3, HardFork::Berlin),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			EvmHardFork::schedule_hard_fork(Origin::root(), 3, HardFork::London),
+			Error::<Test>::UnsupportedHardFork
+		);
+		assert_noop!(
+			EvmHardFork::schedule_hard_fork(Origin::root(), 1, HardFork::Berlin),
+			Error::<Test>::BlockInPast
+		);
+	});
+}
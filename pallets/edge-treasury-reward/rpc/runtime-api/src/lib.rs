@@ -0,0 +1,37 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definition for the treasury reward module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// These clippy lints are disabled because the macro-generated code triggers them.
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+
+pub use treasury_reward::MintingProjection;
+
+sp_api::decl_runtime_apis! {
+	/// The API to query projected treasury minting.
+	pub trait TreasuryRewardApi<Balance, BlockNumber> where
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// Projects minting over the `horizon` blocks following the queried
+		/// block.
+		fn projection(horizon: BlockNumber) -> MintingProjection<Balance, BlockNumber>;
+	}
+}
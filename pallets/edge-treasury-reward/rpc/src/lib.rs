@@ -0,0 +1,110 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Node-specific RPC methods for interaction with the treasury reward module.
+
+use std::{convert::TryInto, marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, MaybeDisplay},
+};
+
+pub use edge_treasury_reward_rpc_runtime_api::{MintingProjection, TreasuryRewardApi as TreasuryRewardRuntimeApi};
+
+/// The runtime API call failed.
+const RUNTIME_ERROR: i64 = 1;
+
+/// Treasury reward RPC methods.
+#[rpc]
+pub trait TreasuryRewardApi<BlockHash, Balance, BlockNumber> {
+	/// Returns the next minting block and its payout, the amount minted so
+	/// far and the total issuance projected `horizon` blocks after `at`.
+	///
+	/// Balances are returned as hex strings when they do not fit in a
+	/// JavaScript number.
+	#[rpc(name = "treasuryReward_projection")]
+	fn projection(
+		&self,
+		horizon: BlockNumber,
+		at: Option<BlockHash>,
+	) -> Result<MintingProjection<NumberOrHex, BlockNumber>>;
+}
+
+/// An implementation of treasury reward specific RPC methods.
+pub struct TreasuryReward<C, B> {
+	client: Arc<C>,
+	_marker: PhantomData<B>,
+}
+
+impl<C, B> TreasuryReward<C, B> {
+	/// Create new `TreasuryReward` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		TreasuryReward {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, Block, Balance, BlockNumber> TreasuryRewardApi<<Block as BlockT>::Hash, Balance, BlockNumber>
+	for TreasuryReward<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static,
+	C: ProvideRuntimeApi<Block>,
+	C: HeaderBackend<Block>,
+	C::Api: TreasuryRewardRuntimeApi<Block, Balance, BlockNumber>,
+	Balance: Codec + MaybeDisplay + Copy + TryInto<NumberOrHex>,
+	BlockNumber: Codec,
+{
+	fn projection(
+		&self,
+		horizon: BlockNumber,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<MintingProjection<NumberOrHex, BlockNumber>> {
+		let api = self.client.runtime_api();
+		// If the block hash is not supplied assume the best block.
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let projection = api.projection(&at, horizon).map_err(|e| Error {
+			code: ErrorCode::ServerError(RUNTIME_ERROR),
+			message: "Unable to query the minting projection.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})?;
+
+		let try_into_rpc_balance = |value: Balance| {
+			value.try_into().map_err(|_| Error {
+				code: ErrorCode::InvalidParams,
+				message: format!("{} doesn't fit in NumberOrHex representation", value),
+				data: None,
+			})
+		};
+
+		Ok(MintingProjection {
+			next_minting_block: projection.next_minting_block,
+			payout: try_into_rpc_balance(projection.payout)?,
+			total_minted: try_into_rpc_balance(projection.total_minted)?,
+			projected_issuance: try_into_rpc_balance(projection.projected_issuance)?,
+		})
+	}
+}
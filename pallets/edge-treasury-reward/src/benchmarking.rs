@@ -0,0 +1,144 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Treasury reward pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
+use frame_support::traits::OnFinalize;
+use frame_system::RawOrigin;
+use sp_runtime::traits::SaturatedConversion;
+
+const SEED: u32 = 0;
+
+fn recipients<T: Config>(r: u32) -> Vec<(T::AccountId, Permill)> {
+	let share = Permill::from_rational(1u32, T::MaxRecipients::get() + 1);
+	(0..r).map(|i| (account("recipient", i, SEED), share)).collect()
+}
+
+/// A curve of `p` points at 100%, one per block from block 0.
+fn curve<T: Config>(p: u32) -> PayoutSchedule<T::BlockNumber> {
+	PayoutSchedule::Curve((0..p).map(|i| (i.into(), Perbill::one())).collect())
+}
+
+benchmarks! {
+	set_current_payout {
+		let payout: BalanceOf<T> = 1_000u32.into();
+	}: _(RawOrigin::Root, payout)
+	verify {
+		assert_eq!(CurrentPayout::<T>::get(), payout);
+	}
+
+	set_minting_interval {
+		let interval: T::BlockNumber = 10u32.into();
+	}: _(RawOrigin::Root, interval)
+	verify {
+		assert_eq!(MintingInterval::<T>::get(), interval);
+	}
+
+	set_payout_schedule {
+		let p in 1 .. T::MaxCurvePoints::get();
+		let schedule = curve::<T>(p);
+	}: _(RawOrigin::Root, schedule.clone())
+	verify {
+		assert_eq!(CurrentSchedule::<T>::get(), schedule);
+	}
+
+	set_recipients {
+		let r in 0 .. T::MaxRecipients::get();
+		let recipients = recipients::<T>(r);
+	}: _(RawOrigin::Root, recipients.clone())
+	verify {
+		assert_eq!(Recipients::<T>::get(), recipients);
+	}
+
+	set_minting_cap {
+		let cap: Option<BalanceOf<T>> = Some(1_000u32.into());
+		MintingFinished::<T>::put(true);
+	}: _(RawOrigin::Root, cap)
+	verify {
+		assert_eq!(MintingCap::<T>::get(), cap);
+		assert!(!MintingFinished::<T>::get());
+	}
+
+	set_minting_end {
+		let end: Option<T::BlockNumber> = Some(1_000u32.into());
+		MintingFinished::<T>::put(true);
+	}: _(RawOrigin::Root, end)
+	verify {
+		assert_eq!(MintingEnd::<T>::get(), end);
+		assert!(!MintingFinished::<T>::get());
+	}
+
+	set_payout_bounds {
+		let bounds: Option<(BalanceOf<T>, BalanceOf<T>)> = Some((1_000u32.into(), 2_000u32.into()));
+	}: _(RawOrigin::Root, bounds)
+	verify {
+		assert_eq!(PayoutBounds::<T>::get(), bounds);
+	}
+
+	set_minting_interval_bounds {
+		let bounds: Option<(T::BlockNumber, T::BlockNumber)> = Some((10u32.into(), 20u32.into()));
+	}: _(RawOrigin::Root, bounds)
+	verify {
+		assert_eq!(MintingIntervalBounds::<T>::get(), bounds);
+	}
+
+	on_finalize_idle {
+		MintingInterval::<T>::put(T::BlockNumber::from(2u32));
+		let now: T::BlockNumber = 1u32.into();
+		frame_system::Pallet::<T>::set_block_number(now);
+		let issuance = <T as Config>::Currency::total_issuance();
+	}: {
+		<Pallet<T> as OnFinalize<T::BlockNumber>>::on_finalize(now);
+	}
+	verify {
+		assert_eq!(<T as Config>::Currency::total_issuance(), issuance);
+	}
+
+	on_finalize_mint {
+		let r in 0 .. T::MaxRecipients::get();
+		let p in 1 .. T::MaxCurvePoints::get();
+		// Large enough for every share to create its recipient's account.
+		let minimum: BalanceOf<T> = <T as Config>::Currency::minimum_balance().max(1u32.into());
+		let payout = minimum.saturating_mul((1_000 * (T::MaxRecipients::get() + 1)).into());
+		// A curve is the costliest schedule, read past its last point. The
+		// issuance is large enough for its rate to reach the cap.
+		CurrentSchedule::<T>::put(curve::<T>(p));
+		let blocks_per_year: BalanceOf<T> = T::BlocksPerYear::get().saturated_into::<u128>().saturated_into();
+		<T as Config>::Currency::make_free_balance_be(
+			&account("whale", 0, SEED),
+			payout.saturating_mul(blocks_per_year),
+		);
+		MintingInterval::<T>::put(T::BlockNumber::from(1u32));
+		Recipients::<T>::put(recipients::<T>(r));
+		// Reaching the cap also finishes minting.
+		MintingCap::<T>::put(payout);
+		let now: T::BlockNumber = p.into();
+		frame_system::Pallet::<T>::set_block_number(now);
+		let issuance = <T as Config>::Currency::total_issuance();
+	}: {
+		<Pallet<T> as OnFinalize<T::BlockNumber>>::on_finalize(now);
+	}
+	verify {
+		assert_eq!(<T as Config>::Currency::total_issuance(), issuance.saturating_add(payout));
+		assert!(MintingFinished::<T>::get());
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
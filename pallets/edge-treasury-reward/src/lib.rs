@@ -23,17 +23,133 @@ pub mod mock;
 #[cfg(test)]
 pub mod tests;
 
-use frame_support::traits::Currency;
+mod benchmarking;
+pub mod migrations;
+pub mod weights;
+
+use codec::{Decode, Encode};
+use frame_support::traits::{Currency, Imbalance};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, One, Saturating, Zero},
+	Perbill, Permill, RuntimeDebug,
+};
+use sp_std::prelude::*;
+
 pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 pub use pallet::*;
+pub use weights::WeightInfo;
+
+/// How much is minted into the treasury every minting interval.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum PayoutSchedule<BlockNumber> {
+	/// Mint the flat `CurrentPayout` amount.
+	Fixed,
+	/// Mint a yearly fraction of the total issuance, spread evenly over the
+	/// minting intervals of a year.
+	Inflation(Perbill),
+	/// Like `Inflation`, but the yearly fraction is interpolated linearly
+	/// between `(block, rate)` points sorted by block. Before the first point
+	/// the first rate applies, after the last point the last rate applies.
+	Curve(Vec<(BlockNumber, Perbill)>),
+}
+
+impl<BlockNumber> Default for PayoutSchedule<BlockNumber> {
+	fn default() -> Self {
+		PayoutSchedule::Fixed
+	}
+}
+
+impl<BlockNumber: AtLeast32BitUnsigned + Copy> PayoutSchedule<BlockNumber> {
+	/// A curve needs at least one point and strictly increasing blocks.
+	pub fn is_valid(&self) -> bool {
+		match self {
+			PayoutSchedule::Curve(points) => !points.is_empty() && points.windows(2).all(|w| w[0].0 < w[1].0),
+			_ => true,
+		}
+	}
+
+	/// The number of curve points, used to weigh schedule updates.
+	pub fn curve_points(&self) -> u32 {
+		match self {
+			PayoutSchedule::Curve(points) => points.len() as u32,
+			_ => 0,
+		}
+	}
+
+	/// The yearly inflation rate at block `now`, or `None` for the fixed
+	/// schedule.
+	pub fn annual_rate(&self, now: BlockNumber) -> Option<Perbill> {
+		match self {
+			PayoutSchedule::Fixed => None,
+			PayoutSchedule::Inflation(rate) => Some(*rate),
+			PayoutSchedule::Curve(points) => {
+				let next = points.iter().position(|(at, _)| *at > now);
+				Some(match next {
+					None => points.last().map(|p| p.1).unwrap_or_else(Perbill::zero),
+					Some(0) => points[0].1,
+					Some(i) => {
+						let (from, from_rate) = points[i - 1];
+						let (to, to_rate) = points[i];
+						let progress = Perbill::from_rational(now - from, to - from);
+						let (lo, hi) = (from_rate.deconstruct(), to_rate.deconstruct());
+						if hi >= lo {
+							from_rate.saturating_add(Perbill::from_parts(progress.mul_floor(hi - lo)))
+						} else {
+							from_rate.saturating_sub(Perbill::from_parts(progress.mul_floor(lo - hi)))
+						}
+					}
+				})
+			}
+		}
+	}
+}
+
+/// The storage layouts of the pallet, used to decide which migrations to run.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+	/// Only `MintingInterval` and `CurrentPayout`.
+	V1_0_0,
+	/// Adds payout schedules, recipients and minting limits.
+	V2_0_0,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1_0_0
+	}
+}
+
+/// Where minting stands now and where it is headed, as reported by the
+/// runtime API.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct MintingProjection<Balance, BlockNumber> {
+	/// The next block that mints, or `None` if minting is disabled or over.
+	pub next_minting_block: Option<BlockNumber>,
+	/// The amount minted at `next_minting_block`.
+	pub payout: Balance,
+	/// The amount minted so far.
+	pub total_minted: Balance,
+	/// The total issuance at the end of the requested horizon, assuming no
+	/// other minting or burning.
+	pub projected_issuance: Balance,
+}
+
+/// The projection of a rate schedule is evaluated in at most this many steps,
+/// with every mint in a step paying the amount of its first mint.
+pub const MAX_PROJECTION_STEPS: u32 = 1_000;
 
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
-	use frame_support::pallet_prelude::*;
+	use frame_support::{dispatch::DispatchResult, pallet_prelude::*, traits::EnsureOrigin};
 	use frame_system::pallet_prelude::*;
-	use sp_runtime::traits::Zero;
+	use sp_runtime::{helpers_128bit::multiply_by_rational, traits::SaturatedConversion};
 
 	/// The pallet's configuration trait.
 	#[pallet::config]
@@ -43,14 +159,98 @@ pub mod pallet {
 
 		/// The account balance
 		type Currency: Currency<Self::AccountId>;
+
+		/// The number of blocks in a year, used to spread yearly inflation over
+		/// minting intervals.
+		#[pallet::constant]
+		type BlocksPerYear: Get<Self::BlockNumber>;
+
+		/// The maximum number of accounts each payout can be split across.
+		#[pallet::constant]
+		type MaxRecipients: Get<u32>;
+
+		/// The maximum number of points in a payout curve.
+		#[pallet::constant]
+		type MaxCurvePoints: Get<u32>;
+
+		/// The origin that can change the fixed payout and the minting
+		/// interval. Unless it is root, it can only move either value by
+		/// `MaxAdjustment` per call, and only within the bounds set by root.
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The largest change, relative to the current value, that a non-root
+		/// `UpdateOrigin` can make to the payout or minting interval in one
+		/// call.
+		#[pallet::constant]
+		type MaxAdjustment: Get<Permill>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The payout curve is empty or its points are not sorted by block.
+		InvalidSchedule,
+		/// More recipients than `MaxRecipients` were given.
+		TooManyRecipients,
+		/// The payout curve has more than `MaxCurvePoints` points.
+		TooManyCurvePoints,
+		/// The recipient shares add up to more than 100%.
+		InvalidRecipientShares,
+		/// The minting interval must be at least one block.
+		ZeroMintingInterval,
+		/// The minting interval must not be longer than a year.
+		MintingIntervalTooLong,
+		/// The cap is not above the amount already minted.
+		CapAlreadyReached,
+		/// The end block is not in the future.
+		EndAlreadyPassed,
+		/// The change is larger than `MaxAdjustment` allows.
+		AdjustmentTooLarge,
+		/// The new value is outside the bounds set by root, or there are none.
+		OutsideAdjustmentBounds,
+		/// The lower bound is above the upper bound.
+		InvalidAdjustmentBounds,
 	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
-	#[pallet::metadata(T::Balance = "Balance", T::BlockNumber = "BlockNumber", T::AccountId = "AccountId")]
+	#[pallet::metadata(
+		BalanceOf<T> = "Balance",
+		T::BlockNumber = "BlockNumber",
+		T::AccountId = "AccountId",
+		PayoutSchedule<T::BlockNumber> = "PayoutSchedule"
+	)]
 	pub enum Event<T: Config> {
-		/// Treasury minting event
-		TreasuryMinting(T::Balance, T::BlockNumber, T::AccountId),
+		/// An amount was minted at a block for a recipient. \[amount, block,
+		/// recipient\]
+		TreasuryMinting(BalanceOf<T>, T::BlockNumber, T::AccountId),
+		/// The fixed payout was changed. \[old, new\]
+		CurrentPayoutChanged(BalanceOf<T>, BalanceOf<T>),
+		/// The minting interval was changed. \[old, new\]
+		MintingIntervalChanged(T::BlockNumber, T::BlockNumber),
+		/// The payout schedule was changed. \[old, new\]
+		PayoutScheduleChanged(PayoutSchedule<T::BlockNumber>, PayoutSchedule<T::BlockNumber>),
+		/// The payout recipients were changed. \[old, new\]
+		RecipientsChanged(Vec<(T::AccountId, Permill)>, Vec<(T::AccountId, Permill)>),
+		/// The cap on the total amount minted was changed. \[old, new\]
+		MintingCapChanged(Option<BalanceOf<T>>, Option<BalanceOf<T>>),
+		/// The block at which minting ends was changed. \[old, new\]
+		MintingEndChanged(Option<T::BlockNumber>, Option<T::BlockNumber>),
+		/// Minting stopped because the cap or end block was reached.
+		/// \[total_minted, block\]
+		MintingFinished(BalanceOf<T>, T::BlockNumber),
+		/// The bounds of the fixed payout were changed. \[old, new\]
+		PayoutBoundsChanged(
+			Option<(BalanceOf<T>, BalanceOf<T>)>,
+			Option<(BalanceOf<T>, BalanceOf<T>)>,
+		),
+		/// The bounds of the minting interval were changed. \[old, new\]
+		MintingIntervalBoundsChanged(
+			Option<(T::BlockNumber, T::BlockNumber)>,
+			Option<(T::BlockNumber, T::BlockNumber)>,
+		),
 	}
 
 	/// The next tree identifier up for grabs
@@ -63,6 +263,52 @@ pub mod pallet {
 	#[pallet::getter(fn current_payout)]
 	pub type CurrentPayout<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
 
+	/// The schedule used to compute each payout
+	#[pallet::storage]
+	#[pallet::getter(fn payout_schedule)]
+	pub type CurrentSchedule<T: Config> = StorageValue<_, PayoutSchedule<T::BlockNumber>, ValueQuery>;
+
+	/// The accounts each payout is split across and their shares. Whatever is
+	/// not allocated goes to the treasury.
+	#[pallet::storage]
+	#[pallet::getter(fn recipients)]
+	pub type Recipients<T: Config> = StorageValue<_, Vec<(T::AccountId, Permill)>, ValueQuery>;
+
+	/// The total amount minted so far
+	#[pallet::storage]
+	#[pallet::getter(fn total_minted)]
+	pub type TotalMinted<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	/// Minting stops once the total amount minted reaches this cap
+	#[pallet::storage]
+	#[pallet::getter(fn minting_cap)]
+	pub type MintingCap<T: Config> = StorageValue<_, BalanceOf<T>, OptionQuery>;
+
+	/// Minting stops at this block
+	#[pallet::storage]
+	#[pallet::getter(fn minting_end)]
+	pub type MintingEnd<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+	/// The lowest and highest fixed payout a non-root `UpdateOrigin` can set
+	#[pallet::storage]
+	#[pallet::getter(fn payout_bounds)]
+	pub type PayoutBounds<T: Config> = StorageValue<_, (BalanceOf<T>, BalanceOf<T>), OptionQuery>;
+
+	/// The shortest and longest interval a non-root `UpdateOrigin` can set
+	#[pallet::storage]
+	#[pallet::getter(fn minting_interval_bounds)]
+	pub type MintingIntervalBounds<T: Config> = StorageValue<_, (T::BlockNumber, T::BlockNumber), OptionQuery>;
+
+	/// Whether minting stopped after reaching the cap or end block
+	#[pallet::storage]
+	#[pallet::getter(fn minting_finished)]
+	pub type MintingFinished<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// The storage layout in use. Chains that started before this was
+	/// introduced read as `V1_0_0`.
+	#[pallet::storage]
+	pub(crate) type StorageVersion<T: Config> = StorageValue<_, Releases, ValueQuery>;
+
 	#[pallet::pallet]
 	pub struct Pallet<T>(PhantomData<T>);
 
@@ -72,6 +318,14 @@ pub mod pallet {
 		pub minting_interval: T::BlockNumber,
 		// endowed accounts for a token + their balances
 		pub current_payout: BalanceOf<T>,
+		// schedule used to compute each payout
+		pub payout_schedule: PayoutSchedule<T::BlockNumber>,
+		// accounts each payout is split across + their shares
+		pub recipients: Vec<(T::AccountId, Permill)>,
+		// cap on the total amount minted
+		pub minting_cap: Option<BalanceOf<T>>,
+		// block at which minting ends
+		pub minting_end: Option<T::BlockNumber>,
 	}
 
 	#[cfg(feature = "std")]
@@ -80,6 +334,10 @@ pub mod pallet {
 			GenesisConfig {
 				minting_interval: Zero::zero(),
 				current_payout: Zero::zero(),
+				payout_schedule: Default::default(),
+				recipients: vec![],
+				minting_cap: None,
+				minting_end: None,
 			}
 		}
 	}
@@ -89,21 +347,49 @@ pub mod pallet {
 		fn build(&self) {
 			MintingInterval::<T>::put(self.minting_interval);
 			CurrentPayout::<T>::put(self.current_payout);
+			assert!(self.payout_schedule.is_valid(), "invalid treasury payout schedule");
+			assert!(
+				self.payout_schedule.curve_points() <= T::MaxCurvePoints::get(),
+				"too many treasury payout curve points"
+			);
+			CurrentSchedule::<T>::put(self.payout_schedule.clone());
+			Pallet::<T>::ensure_valid_recipients(&self.recipients).expect("invalid treasury payout recipients");
+			Recipients::<T>::put(self.recipients.clone());
+			MintingCap::<T>::set(self.minting_cap);
+			MintingEnd::<T>::set(self.minting_end);
+			StorageVersion::<T>::put(Releases::V2_0_0);
 		}
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			if !Self::minting_finished() && (Self::is_minting_block(n) || Self::limit_reached(n)) {
+				T::WeightInfo::on_finalize_mint(Self::recipients().len() as u32, Self::payout_schedule().curve_points())
+			} else {
+				T::WeightInfo::on_finalize_idle()
+			}
+		}
+
 		fn on_finalize(_n: T::BlockNumber) {
-			if <frame_system::Pallet<T>>::block_number() % Self::minting_interval() == Zero::zero() {
-				let reward = Self::current_payout();
-				if reward.is_zero() { return; }
-				<T as Config>::Currency::deposit_creating(&<pallet_treasury::Pallet<T>>::account_id(), reward);
-				Self::deposit_event(Event::TreasuryMinting(
-					<pallet_balances::Pallet<T>>::free_balance(<pallet_treasury::Pallet<T>>::account_id()),
-					<frame_system::Pallet<T>>::block_number(),
-					<pallet_treasury::Pallet<T>>::account_id(),
-				));
+			let now = <frame_system::Pallet<T>>::block_number();
+			if Self::minting_finished() {
+				return;
+			}
+			// Checked on every block, so that minting finishes at the end block
+			// even if it does not mint.
+			if Self::limit_reached(now) {
+				return Self::finish(now);
+			}
+			if !Self::is_minting_block(now) {
+				return;
+			}
+			let reward = Self::capped(Self::compute_payout(now), Self::total_minted());
+			if !reward.is_zero() {
+				Self::mint(reward);
+			}
+			if Self::limit_reached(now) {
+				Self::finish(now);
 			}
 		}
 	}
@@ -111,22 +397,322 @@ pub mod pallet {
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Sets the fixed treasury payout per minting interval.
-		#[pallet::weight(5_000_000)]
+		#[pallet::weight(T::WeightInfo::set_current_payout())]
 		pub(super) fn set_current_payout(origin: OriginFor<T>, payout: BalanceOf<T>) -> DispatchResultWithPostInfo {
-			ensure_root(origin)?;
-			<CurrentPayout<T>>::put(payout);
+			Self::ensure_adjustment(origin, Self::current_payout(), payout, Self::payout_bounds())?;
+			let old = <CurrentPayout<T>>::mutate(|current| sp_std::mem::replace(current, payout));
+			Self::deposit_event(Event::CurrentPayoutChanged(old, payout));
 			Ok(().into())
 		}
 
 		/// Sets the treasury minting interval.
-		#[pallet::weight(5_000_000)]
+		#[pallet::weight(T::WeightInfo::set_minting_interval())]
 		pub(super) fn set_minting_interval(
 			origin: OriginFor<T>,
 			interval: T::BlockNumber,
+		) -> DispatchResultWithPostInfo {
+			Self::ensure_adjustment(
+				origin,
+				Self::minting_interval(),
+				interval,
+				Self::minting_interval_bounds(),
+			)?;
+			ensure!(!interval.is_zero(), Error::<T>::ZeroMintingInterval);
+			ensure!(interval <= T::BlocksPerYear::get(), Error::<T>::MintingIntervalTooLong);
+			let old = <MintingInterval<T>>::mutate(|current| sp_std::mem::replace(current, interval));
+			Self::deposit_event(Event::MintingIntervalChanged(old, interval));
+			Ok(().into())
+		}
+
+		/// Sets the schedule used to compute the treasury payout.
+		#[pallet::weight(T::WeightInfo::set_payout_schedule(schedule.curve_points()))]
+		pub(super) fn set_payout_schedule(
+			origin: OriginFor<T>,
+			schedule: PayoutSchedule<T::BlockNumber>,
 		) -> DispatchResultWithPostInfo {
 			ensure_root(origin)?;
-			<MintingInterval<T>>::put(interval);
+			ensure!(schedule.is_valid(), Error::<T>::InvalidSchedule);
+			ensure!(
+				schedule.curve_points() <= T::MaxCurvePoints::get(),
+				Error::<T>::TooManyCurvePoints
+			);
+			let old = <CurrentSchedule<T>>::mutate(|current| sp_std::mem::replace(current, schedule.clone()));
+			Self::deposit_event(Event::PayoutScheduleChanged(old, schedule));
 			Ok(().into())
 		}
+
+		/// Sets the accounts each payout is split across.
+		#[pallet::weight(T::WeightInfo::set_recipients(recipients.len() as u32))]
+		pub(super) fn set_recipients(
+			origin: OriginFor<T>,
+			recipients: Vec<(T::AccountId, Permill)>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			Self::ensure_valid_recipients(&recipients)?;
+			let old = <Recipients<T>>::mutate(|current| sp_std::mem::replace(current, recipients.clone()));
+			Self::deposit_event(Event::RecipientsChanged(old, recipients));
+			Ok(().into())
+		}
+
+		/// Sets or clears the cap on the total amount minted.
+		#[pallet::weight(T::WeightInfo::set_minting_cap())]
+		pub(super) fn set_minting_cap(origin: OriginFor<T>, cap: Option<BalanceOf<T>>) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			if let Some(cap) = cap {
+				ensure!(cap > Self::total_minted(), Error::<T>::CapAlreadyReached);
+			}
+			let old = <MintingCap<T>>::mutate(|current| sp_std::mem::replace(current, cap));
+			Self::deposit_event(Event::MintingCapChanged(old, cap));
+			Self::resume();
+			Ok(().into())
+		}
+
+		/// Sets or clears the block at which minting ends.
+		#[pallet::weight(T::WeightInfo::set_minting_end())]
+		pub(super) fn set_minting_end(origin: OriginFor<T>, end: Option<T::BlockNumber>) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			if let Some(end) = end {
+				ensure!(
+					end > <frame_system::Pallet<T>>::block_number(),
+					Error::<T>::EndAlreadyPassed
+				);
+			}
+			let old = <MintingEnd<T>>::mutate(|current| sp_std::mem::replace(current, end));
+			Self::deposit_event(Event::MintingEndChanged(old, end));
+			Self::resume();
+			Ok(().into())
+		}
+
+		/// Sets or clears the range a non-root `UpdateOrigin` can move the
+		/// fixed payout within. Without bounds, it cannot change the payout.
+		#[pallet::weight(T::WeightInfo::set_payout_bounds())]
+		pub(super) fn set_payout_bounds(
+			origin: OriginFor<T>,
+			bounds: Option<(BalanceOf<T>, BalanceOf<T>)>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			if let Some((min, max)) = bounds {
+				ensure!(min <= max, Error::<T>::InvalidAdjustmentBounds);
+			}
+			let old = <PayoutBounds<T>>::mutate(|current| sp_std::mem::replace(current, bounds));
+			Self::deposit_event(Event::PayoutBoundsChanged(old, bounds));
+			Ok(().into())
+		}
+
+		/// Sets or clears the range a non-root `UpdateOrigin` can move the
+		/// minting interval within. Without bounds, it cannot change the
+		/// interval.
+		#[pallet::weight(T::WeightInfo::set_minting_interval_bounds())]
+		pub(super) fn set_minting_interval_bounds(
+			origin: OriginFor<T>,
+			bounds: Option<(T::BlockNumber, T::BlockNumber)>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			if let Some((min, max)) = bounds {
+				ensure!(min <= max, Error::<T>::InvalidAdjustmentBounds);
+			}
+			let old = <MintingIntervalBounds<T>>::mutate(|current| sp_std::mem::replace(current, bounds));
+			Self::deposit_event(Event::MintingIntervalBoundsChanged(old, bounds));
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether a payout is minted at block `now`. A zero interval (e.g. the
+		/// default genesis) disables minting.
+		pub fn is_minting_block(now: T::BlockNumber) -> bool {
+			let interval = Self::minting_interval();
+			!interval.is_zero() && (now % interval).is_zero()
+		}
+
+		/// The amount minted at block `now` under the current schedule.
+		pub fn compute_payout(now: T::BlockNumber) -> BalanceOf<T> {
+			Self::payout_at(now, <T as Config>::Currency::total_issuance())
+		}
+
+		/// The next block that mints, or `None` if minting is disabled or a
+		/// limit is reached first.
+		pub fn next_minting_block(now: T::BlockNumber) -> Option<T::BlockNumber> {
+			let interval = Self::minting_interval();
+			if interval.is_zero() || Self::minting_finished() {
+				return None;
+			}
+			let next = now.saturating_add(interval - now % interval);
+			if Self::limit_reached(next) {
+				return None;
+			}
+			Some(next)
+		}
+
+		/// Projects minting over the `horizon` blocks following the current
+		/// block, honouring the cap and the end block.
+		pub fn projection(horizon: T::BlockNumber) -> MintingProjection<BalanceOf<T>, T::BlockNumber> {
+			let now = <frame_system::Pallet<T>>::block_number();
+			let total_minted = Self::total_minted();
+			let issuance = <T as Config>::Currency::total_issuance();
+			let next_minting_block = Self::next_minting_block(now);
+			let payout = next_minting_block.map_or_else(Zero::zero, |next| {
+				Self::capped(Self::payout_at(next, issuance), total_minted)
+			});
+
+			let mut minted = total_minted;
+			let mut projected_issuance = issuance;
+			if let Some(first) = next_minting_block {
+				let interval = Self::minting_interval();
+				let mut last = now.saturating_add(horizon);
+				if let Some(end) = Self::minting_end() {
+					last = last.min(end.saturating_sub(One::one()));
+				}
+				let mut remaining = if last >= first {
+					(last - first) / interval + One::one()
+				} else {
+					Zero::zero()
+				};
+				// A fixed payout is the same for every mint, so one step is exact.
+				let steps = match Self::payout_schedule() {
+					PayoutSchedule::Fixed => One::one(),
+					_ => T::BlockNumber::from(MAX_PROJECTION_STEPS),
+				};
+				let per_step = ((remaining + steps - One::one()) / steps).max(One::one());
+				let mut at = first;
+				while !remaining.is_zero() {
+					let mints = remaining.min(per_step);
+					let mints_balance: BalanceOf<T> = mints.saturated_into::<u128>().saturated_into();
+					let amount = Self::capped(
+						Self::payout_at(at, projected_issuance).saturating_mul(mints_balance),
+						minted,
+					);
+					minted = minted.saturating_add(amount);
+					projected_issuance = projected_issuance.saturating_add(amount);
+					at = at.saturating_add(interval.saturating_mul(mints));
+					remaining -= mints;
+				}
+			}
+
+			MintingProjection {
+				next_minting_block,
+				payout,
+				total_minted,
+				projected_issuance,
+			}
+		}
+
+		/// The amount minted at block `now` if the total issuance is
+		/// `issuance`.
+		fn payout_at(now: T::BlockNumber, issuance: BalanceOf<T>) -> BalanceOf<T> {
+			let rate = match Self::payout_schedule().annual_rate(now) {
+				Some(rate) => rate,
+				None => return Self::current_payout(),
+			};
+			let blocks_per_year = T::BlocksPerYear::get().saturated_into::<u128>();
+			if blocks_per_year.is_zero() {
+				return Zero::zero();
+			}
+			let issuance = issuance.saturated_into::<u128>();
+			let interval = Self::minting_interval().saturated_into::<u128>();
+			multiply_by_rational(rate * issuance, interval, blocks_per_year)
+				.unwrap_or(u128::MAX)
+				.saturated_into()
+		}
+
+		/// Clamps `reward` so that the total minted, `minted` so far, stays
+		/// within the cap.
+		fn capped(reward: BalanceOf<T>, minted: BalanceOf<T>) -> BalanceOf<T> {
+			match Self::minting_cap() {
+				Some(cap) => reward.min(cap.saturating_sub(minted)),
+				None => reward,
+			}
+		}
+
+		/// Splits `reward` across the recipients, sending the rest to the
+		/// treasury. Only what was actually minted counts towards the total.
+		fn mint(reward: BalanceOf<T>) {
+			let mut remaining = reward;
+			let mut minted = BalanceOf::<T>::zero();
+			for (who, share) in Self::recipients() {
+				let amount = share.mul_floor(reward);
+				if amount.is_zero() {
+					continue;
+				}
+				remaining = remaining.saturating_sub(amount);
+				minted = minted.saturating_add(Self::pay(&who, amount));
+			}
+			if !remaining.is_zero() {
+				minted = minted.saturating_add(Self::pay(&<pallet_treasury::Pallet<T>>::account_id(), remaining));
+			}
+			<TotalMinted<T>>::mutate(|total| *total = total.saturating_add(minted));
+		}
+
+		/// Checks that `origin` is root, or is the `UpdateOrigin` and moves
+		/// the value from `old` to `new` by at most `MaxAdjustment`, within
+		/// `bounds`. Repeated calls can therefore not move the value past the
+		/// bounds root set.
+		fn ensure_adjustment<N: AtLeast32BitUnsigned + Copy>(
+			origin: OriginFor<T>,
+			old: N,
+			new: N,
+			bounds: Option<(N, N)>,
+		) -> DispatchResult {
+			if ensure_root(origin.clone()).is_ok() {
+				return Ok(());
+			}
+			T::UpdateOrigin::ensure_origin(origin)?;
+			let (min, max) = bounds.ok_or(Error::<T>::OutsideAdjustmentBounds)?;
+			ensure!(min <= new && new <= max, Error::<T>::OutsideAdjustmentBounds);
+			let change = if new > old { new - old } else { old - new };
+			ensure!(
+				change <= T::MaxAdjustment::get().mul_floor(old),
+				Error::<T>::AdjustmentTooLarge
+			);
+			Ok(())
+		}
+
+		/// Whether the cap or the end block has been reached at block `now`.
+		fn limit_reached(now: T::BlockNumber) -> bool {
+			Self::minting_cap().map_or(false, |cap| Self::total_minted() >= cap)
+				|| Self::minting_end().map_or(false, |end| now >= end)
+		}
+
+		fn finish(now: T::BlockNumber) {
+			<MintingFinished<T>>::put(true);
+			Self::deposit_event(Event::MintingFinished(Self::total_minted(), now));
+		}
+
+		/// Restarts minting if governance lifted the limit that stopped it.
+		fn resume() {
+			if Self::minting_finished() && !Self::limit_reached(<frame_system::Pallet<T>>::block_number()) {
+				<MintingFinished<T>>::kill();
+			}
+		}
+
+		/// Mints `amount` into the account of `who` and returns the amount
+		/// minted, which is nothing if the account does not exist and `amount`
+		/// is below the existential deposit.
+		fn pay(who: &T::AccountId, amount: BalanceOf<T>) -> BalanceOf<T> {
+			let minted = <T as Config>::Currency::deposit_creating(who, amount).peek();
+			if !minted.is_zero() {
+				Self::deposit_event(Event::TreasuryMinting(
+					minted,
+					<frame_system::Pallet<T>>::block_number(),
+					who.clone(),
+				));
+			}
+			minted
+		}
+
+		pub(crate) fn ensure_valid_recipients(recipients: &[(T::AccountId, Permill)]) -> Result<(), Error<T>> {
+			ensure!(
+				recipients.len() <= T::MaxRecipients::get() as usize,
+				Error::<T>::TooManyRecipients
+			);
+			let total = recipients
+				.iter()
+				.fold(0u64, |total, (_, share)| total + share.deconstruct() as u64);
+			ensure!(
+				total <= Permill::one().deconstruct() as u64,
+				Error::<T>::InvalidRecipientShares
+			);
+			Ok(())
+		}
 	}
 }
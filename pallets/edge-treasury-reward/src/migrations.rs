@@ -0,0 +1,79 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage migrations for the treasury reward pallet.
+//!
+//! Each migration has a `pre_migrate` and `post_migrate` check that the
+//! runtime can run around it and that tests can run against mock state.
+
+use super::*;
+use frame_support::{ensure, traits::Get, weights::Weight};
+
+/// Moves from `V1_0_0` to `V2_0_0`.
+///
+/// The new items all start out empty, so the fixed payout keeps going to the
+/// treasury as before. `TotalMinted` counts from the upgrade.
+pub mod v2 {
+	use super::*;
+
+	/// Checks that the storage is still on `V1_0_0`.
+	pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(
+			StorageVersion::<T>::get() == Releases::V1_0_0,
+			"treasury_reward: storage is not V1_0_0"
+		);
+		ensure!(
+			!CurrentSchedule::<T>::exists()
+				&& !Recipients::<T>::exists()
+				&& !TotalMinted::<T>::exists()
+				&& !MintingCap::<T>::exists()
+				&& !MintingEnd::<T>::exists()
+				&& !MintingFinished::<T>::exists(),
+			"treasury_reward: V2_0_0 storage is already in use"
+		);
+		Ok(())
+	}
+
+	/// Records the new layout. Does nothing if it is already recorded.
+	pub fn migrate<T: Config>() -> Weight {
+		if StorageVersion::<T>::get() != Releases::V1_0_0 {
+			return T::DbWeight::get().reads(1);
+		}
+		StorageVersion::<T>::put(Releases::V2_0_0);
+		T::DbWeight::get().reads_writes(1, 1)
+	}
+
+	/// Checks that the storage is on `V2_0_0` and holds a usable schedule.
+	pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(
+			StorageVersion::<T>::get() == Releases::V2_0_0,
+			"treasury_reward: storage is not V2_0_0"
+		);
+		ensure!(
+			Pallet::<T>::payout_schedule().is_valid(),
+			"treasury_reward: invalid payout schedule"
+		);
+		ensure!(
+			Pallet::<T>::payout_schedule().curve_points() <= T::MaxCurvePoints::get(),
+			"treasury_reward: too many payout curve points"
+		);
+		ensure!(
+			Pallet::<T>::ensure_valid_recipients(&Pallet::<T>::recipients()).is_ok(),
+			"treasury_reward: invalid payout recipients"
+		);
+		Ok(())
+	}
+}
@@ -1,8 +1,17 @@
 use super::*;
 use crate as treasury_reward;
-use frame_support::{construct_runtime, parameter_types, traits::GenesisBuild, weights::Weight, PalletId};
-use frame_system::mocking::{MockBlock, MockUncheckedExtrinsic};
+use frame_support::{
+	construct_runtime, parameter_types,
+	traits::{EnsureOrigin, GenesisBuild, Get},
+	weights::Weight,
+	PalletId,
+};
+use frame_system::{
+	mocking::{MockBlock, MockUncheckedExtrinsic},
+	EnsureOneOf, EnsureRoot, RawOrigin,
+};
 use sp_runtime::{traits::One, Permill};
+use std::cell::RefCell;
 
 use sp_core::H256;
 use sp_runtime::{
@@ -68,8 +77,22 @@ impl frame_system::Config for Test {
 	type Version = ();
 }
 
+thread_local! {
+	static EXISTENTIAL_DEPOSIT: RefCell<Balance> = RefCell::new(0);
+}
+
+pub struct ExistentialDeposit;
+impl Get<Balance> for ExistentialDeposit {
+	fn get() -> Balance {
+		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_existential_deposit(deposit: Balance) {
+	EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = deposit);
+}
+
 parameter_types! {
-	pub const ExistentialDeposit: Balance = 0;
 	pub const MaxLocks: u32 = 50;
 	pub const MaxTreeDepth: u8 = 32;
 	pub const CacheBlockLength: u64 = 5;
@@ -113,9 +136,40 @@ impl pallet_treasury::Config for Test {
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub const BlocksPerYear: BlockNumber = 100;
+	pub const MaxRecipients: u32 = 3;
+	pub const MaxCurvePoints: u32 = 4;
+	pub const MaxAdjustment: Permill = Permill::from_percent(10);
+}
+
+/// Stands in for the council: only `BOB` passes.
+pub struct EnsureBob;
+impl EnsureOrigin<Origin> for EnsureBob {
+	type Success = ();
+
+	fn try_origin(o: Origin) -> Result<Self::Success, Origin> {
+		Into::<Result<RawOrigin<AccountId>, Origin>>::into(o).and_then(|o| match o {
+			RawOrigin::Signed(ref who) if *who == BOB => Ok(()),
+			r => Err(Origin::from(r)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn successful_origin() -> Origin {
+		Origin::from(RawOrigin::Signed(BOB))
+	}
+}
+
 impl Config for Test {
+	type BlocksPerYear = BlocksPerYear;
 	type Currency = Balances;
 	type Event = Event;
+	type MaxAdjustment = MaxAdjustment;
+	type MaxCurvePoints = MaxCurvePoints;
+	type MaxRecipients = MaxRecipients;
+	type UpdateOrigin = EnsureOneOf<AccountId, EnsureRoot<AccountId>, EnsureBob>;
+	type WeightInfo = ();
 }
 
 pub type TreasuryCurrencyAdapter = <Test as pallet_treasury::Config>::Currency;
@@ -123,6 +177,7 @@ pub type TreasuryCurrencyAdapter = <Test as pallet_treasury::Config>::Currency;
 pub const ALICE: AccountId = AccountId32::new([0u8; 32]);
 pub const BOB: AccountId = AccountId32::new([1u8; 32]);
 pub const TREASURY_ACCOUNT: AccountId = AccountId32::new([2u8; 32]);
+pub const CHARLIE: AccountId = AccountId32::new([3u8; 32]);
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	use pallet_balances::GenesisConfig as BalancesConfig;
@@ -142,6 +197,10 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	treasury_reward::GenesisConfig::<Test> {
 		current_payout: 9500000,
 		minting_interval: One::one(),
+		payout_schedule: PayoutSchedule::Fixed,
+		recipients: vec![],
+		minting_cap: None,
+		minting_end: None,
 	}
 	.assimilate_storage(&mut t)
 	.unwrap();
@@ -149,6 +208,16 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	t.into()
 }
 
+pub fn treasury_reward_events() -> Vec<crate::Event<Test>> {
+	frame_system::Pallet::<Test>::events()
+		.into_iter()
+		.filter_map(|r| match r.event {
+			Event::treasury_reward(inner) => Some(inner),
+			_ => None,
+		})
+		.collect()
+}
+
 pub fn last_event() -> Event {
 	frame_system::Pallet::<Test>::events()
 		.pop()
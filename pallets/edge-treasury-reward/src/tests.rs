@@ -18,7 +18,8 @@ use super::*;
 use crate::mock::*;
 use mock::{Event, last_event};
 
-use frame_support::traits::OnFinalize;
+use frame_support::{assert_noop, assert_ok, traits::OnFinalize};
+use sp_runtime::{DispatchError, Perbill, Permill};
 
 #[test]
 fn basic_setup_works() {
@@ -62,7 +63,7 @@ fn setting_treasury_block_reward() {
 		<TreasuryReward as OnFinalize<u64>>::on_finalize(2);
 		assert_eq!(Balances::free_balance(treasury_address.clone()) == 19000000, true);
 		assert_eq!(last_event(), Event::treasury_reward(
-                    crate::Event::TreasuryMinting(19000000, 2, treasury_address.clone())
+                    crate::Event::TreasuryMinting(9500000, 2, treasury_address.clone())
                 ));
 
 		// Reduce minting interval to 2
@@ -74,7 +75,7 @@ fn setting_treasury_block_reward() {
 		<TreasuryReward as OnFinalize<u64>>::on_finalize(3);
 		assert_eq!(Balances::free_balance(treasury_address.clone()) == 19000000, true);
 		assert_eq!(last_event(), Event::treasury_reward(
-                    crate::Event::TreasuryMinting(19000000, 2, treasury_address.clone())
+                    crate::Event::MintingIntervalChanged(1, 2)
                 ));
 
 		// 95 EDG to block 4
@@ -82,7 +83,7 @@ fn setting_treasury_block_reward() {
 		<TreasuryReward as OnFinalize<u64>>::on_finalize(4);
 		assert_eq!(Balances::free_balance(treasury_address.clone()) == 19000095, true);
 		assert_eq!(last_event(), Event::treasury_reward(
-                    crate::Event::TreasuryMinting(19000095, 4, treasury_address.clone())
+                    crate::Event::TreasuryMinting(95, 4, treasury_address.clone())
                 ));
 
 		// Reduce payout to 0
@@ -93,7 +94,7 @@ fn setting_treasury_block_reward() {
 		<TreasuryReward as OnFinalize<u64>>::on_finalize(5);
 		assert_eq!(Balances::free_balance(treasury_address.clone()) == 19000095, true);
 		assert_eq!(last_event(), Event::treasury_reward(
-                    crate::Event::TreasuryMinting(19000095, 4, treasury_address.clone())
+                    crate::Event::CurrentPayoutChanged(95, 0)
                 ));
 
 		// 0 EDG to block 6
@@ -101,7 +102,7 @@ fn setting_treasury_block_reward() {
 		<TreasuryReward as OnFinalize<u64>>::on_finalize(6);
 		assert_eq!(Balances::free_balance(treasury_address.clone()) == 19000095, true);
 		assert_eq!(last_event(), Event::treasury_reward(
-                    crate::Event::TreasuryMinting(19000095, 4, treasury_address.clone())
+                    crate::Event::CurrentPayoutChanged(95, 0)
                 ));
 
 		// Increase payout to 105
@@ -112,7 +113,7 @@ fn setting_treasury_block_reward() {
 		<TreasuryReward as OnFinalize<u64>>::on_finalize(7);
 		assert_eq!(Balances::free_balance(treasury_address.clone()) == 19000095, true);
 		assert_eq!(last_event(), Event::treasury_reward(
-                    crate::Event::TreasuryMinting(19000095, 4, treasury_address.clone())
+                    crate::Event::CurrentPayoutChanged(0, 105)
                 ));
 
 		// 105 EDG to block 8
@@ -120,7 +121,7 @@ fn setting_treasury_block_reward() {
 		<TreasuryReward as OnFinalize<u64>>::on_finalize(8);
 		assert_eq!(Balances::free_balance(treasury_address.clone()) == 19000200, true);
 		assert_eq!(last_event(), Event::treasury_reward(
-                    crate::Event::TreasuryMinting(19000200, 8, treasury_address.clone())
+                    crate::Event::TreasuryMinting(105, 8, treasury_address.clone())
                 ));
 
 		// Reduce payout to 10
@@ -133,7 +134,7 @@ fn setting_treasury_block_reward() {
 		<TreasuryReward as OnFinalize<u64>>::on_finalize(9);
 		assert_eq!(Balances::free_balance(treasury_address.clone()) == 19000210, true);
 		assert_eq!(last_event(), Event::treasury_reward(
-                    crate::Event::TreasuryMinting(19000210, 9, treasury_address.clone())
+                    crate::Event::TreasuryMinting(10, 9, treasury_address.clone())
                 ));
 
 		// 10 EDG to block 10
@@ -141,7 +142,471 @@ fn setting_treasury_block_reward() {
 		<TreasuryReward as OnFinalize<u64>>::on_finalize(10);
 		assert_eq!(Balances::free_balance(treasury_address.clone()) == 19000220, true);
 		assert_eq!(last_event(), Event::treasury_reward(
-                    crate::Event::TreasuryMinting(19000220, 10, treasury_address.clone())
+                    crate::Event::TreasuryMinting(10, 10, treasury_address.clone())
                 ));
 	});
 }
+
+#[test]
+fn inflation_schedule_mints_share_of_issuance() {
+	new_test_ext().execute_with(|| {
+		let treasury_address = Treasury::account_id();
+		// 10% a year over 100 blocks a year, minted every block
+		assert_ok!(TreasuryReward::set_payout_schedule(
+			frame_system::RawOrigin::Root.into(),
+			PayoutSchedule::Inflation(Perbill::from_percent(10)),
+		));
+
+		System::set_block_number(1);
+		<TreasuryReward as OnFinalize<u64>>::on_finalize(1);
+		assert_eq!(Balances::free_balance(treasury_address.clone()), 3_000_000_000_000_000);
+
+		// The next payout grows with the issuance
+		System::set_block_number(2);
+		<TreasuryReward as OnFinalize<u64>>::on_finalize(2);
+		assert_eq!(Balances::free_balance(treasury_address.clone()), 6_003_000_000_000_000);
+	});
+}
+
+#[test]
+fn curve_schedule_interpolates_between_points() {
+	new_test_ext().execute_with(|| {
+		let schedule = PayoutSchedule::Curve(vec![
+			(10, Perbill::from_percent(20)),
+			(20, Perbill::from_percent(10)),
+		]);
+		assert_eq!(schedule.annual_rate(0), Some(Perbill::from_percent(20)));
+		assert_eq!(schedule.annual_rate(15), Some(Perbill::from_percent(15)));
+		assert_eq!(schedule.annual_rate(20), Some(Perbill::from_percent(10)));
+		assert_eq!(schedule.annual_rate(1_000), Some(Perbill::from_percent(10)));
+
+		assert_ok!(TreasuryReward::set_payout_schedule(frame_system::RawOrigin::Root.into(), schedule));
+		assert_eq!(TreasuryReward::compute_payout(5), 6_000_000_000_000_000);
+		assert_eq!(TreasuryReward::compute_payout(15), 4_500_000_000_000_000);
+		assert_eq!(TreasuryReward::compute_payout(30), 3_000_000_000_000_000);
+
+		// Switching back to the fixed schedule uses the current payout again
+		assert_ok!(TreasuryReward::set_payout_schedule(
+			frame_system::RawOrigin::Root.into(),
+			PayoutSchedule::Fixed,
+		));
+		assert_eq!(TreasuryReward::compute_payout(30), 9500000);
+	});
+}
+
+#[test]
+fn invalid_payout_schedules_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TreasuryReward::set_payout_schedule(frame_system::RawOrigin::Root.into(), PayoutSchedule::Curve(vec![])),
+			Error::<Test>::InvalidSchedule,
+		);
+		assert_noop!(
+			TreasuryReward::set_payout_schedule(
+				frame_system::RawOrigin::Root.into(),
+				PayoutSchedule::Curve(vec![(20, Perbill::one()), (10, Perbill::zero())]),
+			),
+			Error::<Test>::InvalidSchedule,
+		);
+		assert_noop!(
+			TreasuryReward::set_payout_schedule(
+				frame_system::RawOrigin::Root.into(),
+				PayoutSchedule::Curve((0..5).map(|i| (i * 10, Perbill::zero())).collect()),
+			),
+			Error::<Test>::TooManyCurvePoints,
+		);
+		assert_noop!(
+			TreasuryReward::set_payout_schedule(Origin::signed(ALICE), PayoutSchedule::Fixed),
+			DispatchError::BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn payout_is_split_across_recipients() {
+	new_test_ext().execute_with(|| {
+		let treasury_address = Treasury::account_id();
+		System::set_block_number(1);
+		assert_ok!(TreasuryReward::set_recipients(
+			frame_system::RawOrigin::Root.into(),
+			vec![(ALICE, Permill::from_percent(50)), (BOB, Permill::from_percent(30))],
+		));
+		<TreasuryReward as OnFinalize<u64>>::on_finalize(1);
+		assert_eq!(Balances::free_balance(ALICE), 1_000_000_000_004_750_000);
+		assert_eq!(Balances::free_balance(BOB), 1_000_000_000_002_850_000);
+		// The unallocated 20% goes to the treasury
+		assert_eq!(Balances::free_balance(treasury_address.clone()), 1_900_000);
+		assert_eq!(treasury_reward_events(), vec![
+			crate::Event::RecipientsChanged(vec![], vec![
+				(ALICE, Permill::from_percent(50)),
+				(BOB, Permill::from_percent(30)),
+			]),
+			crate::Event::TreasuryMinting(4_750_000, 1, ALICE),
+			crate::Event::TreasuryMinting(2_850_000, 1, BOB),
+			crate::Event::TreasuryMinting(1_900_000, 1, treasury_address),
+		]);
+	});
+}
+
+#[test]
+fn shares_below_the_existential_deposit_are_not_minted() {
+	new_test_ext().execute_with(|| {
+		let treasury_address = Treasury::account_id();
+		let issuance = Balances::total_issuance();
+		set_existential_deposit(1_000_000);
+		System::set_block_number(1);
+		assert_ok!(TreasuryReward::set_recipients(
+			frame_system::RawOrigin::Root.into(),
+			vec![(CHARLIE, Permill::from_percent(10))],
+		));
+		<TreasuryReward as OnFinalize<u64>>::on_finalize(1);
+		// CHARLIE has no account and 950000 is below the existential deposit
+		assert_eq!(Balances::free_balance(CHARLIE), 0);
+		assert_eq!(Balances::free_balance(treasury_address.clone()), 8_550_000);
+		assert_eq!(Balances::total_issuance(), issuance + 8_550_000);
+		assert_eq!(TreasuryReward::total_minted(), 8_550_000);
+		assert_eq!(treasury_reward_events(), vec![
+			crate::Event::RecipientsChanged(vec![], vec![(CHARLIE, Permill::from_percent(10))]),
+			crate::Event::TreasuryMinting(8_550_000, 1, treasury_address),
+		]);
+	});
+}
+
+#[test]
+fn invalid_recipients_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TreasuryReward::set_recipients(
+				frame_system::RawOrigin::Root.into(),
+				vec![(ALICE, Permill::from_percent(60)), (BOB, Permill::from_percent(50))],
+			),
+			Error::<Test>::InvalidRecipientShares,
+		);
+		assert_noop!(
+			TreasuryReward::set_recipients(
+				frame_system::RawOrigin::Root.into(),
+				vec![
+					(ALICE, Permill::from_percent(10)),
+					(BOB, Permill::from_percent(10)),
+					(CHARLIE, Permill::from_percent(10)),
+					(TREASURY_ACCOUNT, Permill::from_percent(10)),
+				],
+			),
+			Error::<Test>::TooManyRecipients,
+		);
+		assert_noop!(
+			TreasuryReward::set_recipients(Origin::signed(ALICE), vec![]),
+			DispatchError::BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn parameter_changes_emit_old_and_new_values() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TreasuryReward::set_current_payout(frame_system::RawOrigin::Root.into(), 95));
+		assert_ok!(TreasuryReward::set_minting_interval(frame_system::RawOrigin::Root.into(), 10));
+		assert_ok!(TreasuryReward::set_payout_schedule(
+			frame_system::RawOrigin::Root.into(),
+			PayoutSchedule::Inflation(Perbill::from_percent(5)),
+		));
+		assert_eq!(treasury_reward_events(), vec![
+			crate::Event::CurrentPayoutChanged(9500000, 95),
+			crate::Event::MintingIntervalChanged(1, 10),
+			crate::Event::PayoutScheduleChanged(
+				PayoutSchedule::Fixed,
+				PayoutSchedule::Inflation(Perbill::from_percent(5)),
+			),
+		]);
+	});
+}
+
+#[test]
+fn zero_minting_interval_disables_minting() {
+	new_test_ext().execute_with(|| {
+		let treasury_address = Treasury::account_id();
+		MintingInterval::<Test>::put(0);
+
+		System::set_block_number(1);
+		<TreasuryReward as OnFinalize<u64>>::on_finalize(1);
+		assert_eq!(Balances::free_balance(treasury_address), 0);
+		assert_eq!(treasury_reward_events(), vec![]);
+	});
+}
+
+#[test]
+fn invalid_minting_intervals_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TreasuryReward::set_minting_interval(frame_system::RawOrigin::Root.into(), 0),
+			Error::<Test>::ZeroMintingInterval,
+		);
+		assert_noop!(
+			TreasuryReward::set_minting_interval(frame_system::RawOrigin::Root.into(), BlocksPerYear::get() + 1),
+			Error::<Test>::MintingIntervalTooLong,
+		);
+		assert_noop!(
+			TreasuryReward::set_minting_interval(Origin::signed(ALICE), 2),
+			DispatchError::BadOrigin,
+		);
+		assert_ok!(TreasuryReward::set_minting_interval(
+			frame_system::RawOrigin::Root.into(),
+			BlocksPerYear::get()
+		));
+		assert_eq!(TreasuryReward::minting_interval(), BlocksPerYear::get());
+	});
+}
+
+#[test]
+fn minting_stops_at_cap() {
+	new_test_ext().execute_with(|| {
+		let treasury_address = Treasury::account_id();
+		System::set_block_number(1);
+		assert_ok!(TreasuryReward::set_minting_cap(frame_system::RawOrigin::Root.into(), Some(20_000_000)));
+
+		for n in 1..=4 {
+			System::set_block_number(n);
+			<TreasuryReward as OnFinalize<u64>>::on_finalize(n);
+		}
+		// The last payout is cut down to the cap
+		assert_eq!(Balances::free_balance(treasury_address.clone()), 20_000_000);
+		assert_eq!(TreasuryReward::total_minted(), 20_000_000);
+		assert!(TreasuryReward::minting_finished());
+		assert_eq!(treasury_reward_events(), vec![
+			crate::Event::MintingCapChanged(None, Some(20_000_000)),
+			crate::Event::TreasuryMinting(9500000, 1, treasury_address.clone()),
+			crate::Event::TreasuryMinting(9500000, 2, treasury_address.clone()),
+			crate::Event::TreasuryMinting(1000000, 3, treasury_address.clone()),
+			crate::Event::MintingFinished(20_000_000, 3),
+		]);
+
+		assert_noop!(
+			TreasuryReward::set_minting_cap(frame_system::RawOrigin::Root.into(), Some(20_000_000)),
+			Error::<Test>::CapAlreadyReached,
+		);
+
+		// Raising the cap resumes minting
+		assert_ok!(TreasuryReward::set_minting_cap(frame_system::RawOrigin::Root.into(), Some(30_000_000)));
+		assert!(!TreasuryReward::minting_finished());
+		System::set_block_number(5);
+		<TreasuryReward as OnFinalize<u64>>::on_finalize(5);
+		assert_eq!(Balances::free_balance(treasury_address.clone()), 29_500_000);
+	});
+}
+
+#[test]
+fn minting_stops_at_end_block() {
+	new_test_ext().execute_with(|| {
+		let treasury_address = Treasury::account_id();
+		System::set_block_number(1);
+		assert_ok!(TreasuryReward::set_minting_end(frame_system::RawOrigin::Root.into(), Some(3)));
+
+		for n in 1..=4 {
+			System::set_block_number(n);
+			<TreasuryReward as OnFinalize<u64>>::on_finalize(n);
+		}
+		assert_eq!(Balances::free_balance(treasury_address.clone()), 19_000_000);
+		assert!(TreasuryReward::minting_finished());
+		assert_eq!(last_event(), Event::treasury_reward(crate::Event::MintingFinished(19_000_000, 3)));
+
+		assert_noop!(
+			TreasuryReward::set_minting_end(frame_system::RawOrigin::Root.into(), Some(4)),
+			Error::<Test>::EndAlreadyPassed,
+		);
+		assert_noop!(
+			TreasuryReward::set_minting_end(Origin::signed(ALICE), None),
+			DispatchError::BadOrigin,
+		);
+
+		// Clearing the end block resumes minting
+		assert_ok!(TreasuryReward::set_minting_end(frame_system::RawOrigin::Root.into(), None));
+		System::set_block_number(5);
+		<TreasuryReward as OnFinalize<u64>>::on_finalize(5);
+		assert_eq!(Balances::free_balance(treasury_address), 28_500_000);
+	});
+}
+
+#[test]
+fn minting_stops_at_an_end_block_that_does_not_mint() {
+	new_test_ext().execute_with(|| {
+		let treasury_address = Treasury::account_id();
+		System::set_block_number(1);
+		assert_ok!(TreasuryReward::set_minting_interval(frame_system::RawOrigin::Root.into(), 2));
+		assert_ok!(TreasuryReward::set_minting_end(frame_system::RawOrigin::Root.into(), Some(5)));
+
+		for n in 1..=5 {
+			System::set_block_number(n);
+			<TreasuryReward as OnFinalize<u64>>::on_finalize(n);
+		}
+		// Blocks 2 and 4 mint, and minting finishes at block 5 rather than 6
+		assert_eq!(Balances::free_balance(treasury_address), 19_000_000);
+		assert!(TreasuryReward::minting_finished());
+		assert_eq!(last_event(), Event::treasury_reward(crate::Event::MintingFinished(19_000_000, 5)));
+	});
+}
+
+#[test]
+fn update_origin_is_bounded_by_max_adjustment() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TreasuryReward::set_payout_bounds(
+			frame_system::RawOrigin::Root.into(),
+			Some((1_000_000, 20_000_000)),
+		));
+		assert_ok!(TreasuryReward::set_minting_interval_bounds(
+			frame_system::RawOrigin::Root.into(),
+			Some((1, 10)),
+		));
+
+		// 10% of the current payout of 9500000
+		assert_ok!(TreasuryReward::set_current_payout(Origin::signed(BOB), 10_450_000));
+		assert_eq!(TreasuryReward::current_payout(), 10_450_000);
+		assert_noop!(
+			TreasuryReward::set_current_payout(Origin::signed(BOB), 12_000_000),
+			Error::<Test>::AdjustmentTooLarge,
+		);
+		assert_ok!(TreasuryReward::set_current_payout(Origin::signed(BOB), 9_405_000));
+
+		// 10% of an interval of one block rounds down to nothing
+		assert_noop!(
+			TreasuryReward::set_minting_interval(Origin::signed(BOB), 2),
+			Error::<Test>::AdjustmentTooLarge,
+		);
+
+		assert_noop!(
+			TreasuryReward::set_current_payout(Origin::signed(ALICE), 9_500_000),
+			DispatchError::BadOrigin,
+		);
+
+		// Root is not bound
+		assert_ok!(TreasuryReward::set_current_payout(frame_system::RawOrigin::Root.into(), 1));
+		assert_ok!(TreasuryReward::set_minting_interval(frame_system::RawOrigin::Root.into(), 50));
+	});
+}
+
+#[test]
+fn update_origin_is_bounded_by_root_set_bounds() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		// Without bounds the update origin cannot change either value
+		assert_noop!(
+			TreasuryReward::set_current_payout(Origin::signed(BOB), 9_600_000),
+			Error::<Test>::OutsideAdjustmentBounds,
+		);
+		assert_noop!(
+			TreasuryReward::set_minting_interval(Origin::signed(BOB), 1),
+			Error::<Test>::OutsideAdjustmentBounds,
+		);
+
+		assert_noop!(
+			TreasuryReward::set_payout_bounds(frame_system::RawOrigin::Root.into(), Some((2, 1))),
+			Error::<Test>::InvalidAdjustmentBounds,
+		);
+		assert_noop!(
+			TreasuryReward::set_payout_bounds(Origin::signed(BOB), None),
+			DispatchError::BadOrigin,
+		);
+		assert_ok!(TreasuryReward::set_payout_bounds(
+			frame_system::RawOrigin::Root.into(),
+			Some((9_000_000, 11_000_000)),
+		));
+
+		// Repeated calls cannot ratchet the payout past the upper bound
+		assert_ok!(TreasuryReward::set_current_payout(Origin::signed(BOB), 10_450_000));
+		assert_ok!(TreasuryReward::set_current_payout(Origin::signed(BOB), 11_000_000));
+		assert_noop!(
+			TreasuryReward::set_current_payout(Origin::signed(BOB), 11_000_001),
+			Error::<Test>::OutsideAdjustmentBounds,
+		);
+
+		// Root is not bound
+		assert_ok!(TreasuryReward::set_current_payout(frame_system::RawOrigin::Root.into(), 20_000_000));
+		assert_eq!(
+			treasury_reward_events()[0],
+			crate::Event::PayoutBoundsChanged(None, Some((9_000_000, 11_000_000)))
+		);
+	});
+}
+
+#[test]
+fn projection_follows_interval_cap_and_end() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let issuance = Balances::total_issuance();
+		assert_ok!(TreasuryReward::set_minting_interval(frame_system::RawOrigin::Root.into(), 2));
+
+		// Mints at blocks 2, 4, 6, 8 and 10
+		assert_eq!(TreasuryReward::projection(10), MintingProjection {
+			next_minting_block: Some(2),
+			payout: 9_500_000,
+			total_minted: 0,
+			projected_issuance: issuance + 47_500_000,
+		});
+
+		// The end block itself does not mint
+		assert_ok!(TreasuryReward::set_minting_end(frame_system::RawOrigin::Root.into(), Some(8)));
+		assert_eq!(TreasuryReward::projection(10).projected_issuance, issuance + 28_500_000);
+
+		// The last payout is cut down to the cap
+		assert_ok!(TreasuryReward::set_minting_cap(frame_system::RawOrigin::Root.into(), Some(20_000_000)));
+		assert_eq!(TreasuryReward::projection(10).projected_issuance, issuance + 20_000_000);
+
+		// Nothing is projected once minting is over
+		System::set_block_number(8);
+		assert_eq!(TreasuryReward::projection(10), MintingProjection {
+			next_minting_block: None,
+			payout: 0,
+			total_minted: 0,
+			projected_issuance: issuance,
+		});
+	});
+}
+
+#[test]
+fn projection_compounds_rate_schedules() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TreasuryReward::set_payout_schedule(
+			frame_system::RawOrigin::Root.into(),
+			PayoutSchedule::Inflation(Perbill::from_percent(10)),
+		));
+		let issuance = Balances::total_issuance();
+
+		// Matches the two mints of `inflation_schedule_mints_share_of_issuance`
+		let projection = TreasuryReward::projection(2);
+		assert_eq!(projection.next_minting_block, Some(1));
+		assert_eq!(projection.payout, 3_000_000_000_000_000);
+		assert_eq!(projection.projected_issuance, issuance + 6_003_000_000_000_000);
+	});
+}
+
+#[test]
+fn v2_migration_upgrades_v1_storage() {
+	new_test_ext().execute_with(|| {
+		let treasury_address = Treasury::account_id();
+		// Genesis already uses the latest layout
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V2_0_0);
+		assert!(migrations::v2::pre_migrate::<Test>().is_err());
+
+		// Roll back to what a V1_0_0 chain has in storage
+		StorageVersion::<Test>::kill();
+		CurrentSchedule::<Test>::kill();
+		Recipients::<Test>::kill();
+		assert_ok!(migrations::v2::pre_migrate::<Test>());
+		assert!(migrations::v2::post_migrate::<Test>().is_err());
+
+		migrations::v2::migrate::<Test>();
+		assert_ok!(migrations::v2::post_migrate::<Test>());
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V2_0_0);
+		assert_eq!(TreasuryReward::minting_interval(), 1);
+		assert_eq!(TreasuryReward::current_payout(), 9500000);
+
+		// Running it again changes nothing
+		migrations::v2::migrate::<Test>();
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V2_0_0);
+
+		// Minting carries on as before the upgrade
+		System::set_block_number(1);
+		<TreasuryReward as OnFinalize<u64>>::on_finalize(1);
+		assert_eq!(Balances::free_balance(treasury_address), 9500000);
+	});
+}
@@ -0,0 +1,192 @@
+// Copyright 2018-2020 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for treasury_reward
+//!
+//! THESE ARE PLACEHOLDERS, NOT BENCHMARK RESULTS. The numbers were picked by
+//! hand to follow the shape of the benchmarks in `benchmarking.rs`, and must be
+//! replaced by the output of a run on reference hardware:
+//!
+//! ```sh
+//! ./target/release/edgeware benchmark \
+//! 	--chain=dev \
+//! 	--steps=50 \
+//! 	--repeat=20 \
+//! 	--pallet=treasury_reward \
+//! 	--extrinsic='*' \
+//! 	--execution=wasm \
+//! 	--wasm-execution=compiled \
+//! 	--heap-pages=4096 \
+//! 	--output=./pallets/edge-treasury-reward/src/weights.rs
+//! ```
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for treasury_reward.
+pub trait WeightInfo {
+	fn set_current_payout() -> Weight;
+	fn set_minting_interval() -> Weight;
+	fn set_payout_schedule(p: u32) -> Weight;
+	fn set_recipients(r: u32) -> Weight;
+	fn set_minting_cap() -> Weight;
+	fn set_minting_end() -> Weight;
+	fn set_payout_bounds() -> Weight;
+	fn set_minting_interval_bounds() -> Weight;
+	fn on_finalize_idle() -> Weight;
+	fn on_finalize_mint(r: u32, p: u32) -> Weight;
+}
+
+/// Weights for treasury_reward using the Edgeware node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn set_current_payout() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+
+	fn set_minting_interval() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+
+	fn set_payout_schedule(p: u32) -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add((64_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+
+	fn set_recipients(r: u32) -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add((120_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+
+	fn set_minting_cap() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+
+	fn set_minting_end() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+
+	fn set_payout_bounds() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+
+	fn set_minting_interval_bounds() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+
+	fn on_finalize_idle() -> Weight {
+		(2_000_000 as Weight).saturating_add(T::DbWeight::get().reads(4 as Weight))
+	}
+
+	fn on_finalize_mint(r: u32, p: u32) -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add((31_000_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add((150_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(r as Weight)))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn set_current_payout() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+
+	fn set_minting_interval() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+
+	fn set_payout_schedule(p: u32) -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add((64_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+
+	fn set_recipients(r: u32) -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add((120_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+
+	fn set_minting_cap() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+
+	fn set_minting_end() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+
+	fn set_payout_bounds() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+
+	fn set_minting_interval_bounds() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+
+	fn on_finalize_idle() -> Weight {
+		(2_000_000 as Weight).saturating_add(RocksDbWeight::get().reads(4 as Weight))
+	}
+
+	fn on_finalize_mint(r: u32, p: u32) -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add((31_000_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add((150_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(r as Weight)))
+	}
+}